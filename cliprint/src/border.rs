@@ -0,0 +1,302 @@
+//! glyphs of the frame, and how two glyphs join when frames touch
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::{BorderStyle, FrameSettings};
+use crate::text::{display_width, grapheme_width};
+
+const UP: u8 = 1;
+const DOWN: u8 = 2;
+const LEFT: u8 = 4;
+const RIGHT: u8 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Line {
+    Light,
+    Heavy,
+    Double,
+    Ascii,
+}
+
+/// every glyph with the line it is drawn with and the arms it has
+const GLYPHS: &[(char, Line, u8)] = &[
+    ('─', Line::Light, LEFT | RIGHT),
+    ('│', Line::Light, UP | DOWN),
+    ('┌', Line::Light, DOWN | RIGHT),
+    ('┐', Line::Light, DOWN | LEFT),
+    ('└', Line::Light, UP | RIGHT),
+    ('┘', Line::Light, UP | LEFT),
+    ('├', Line::Light, UP | DOWN | RIGHT),
+    ('┤', Line::Light, UP | DOWN | LEFT),
+    ('┬', Line::Light, DOWN | LEFT | RIGHT),
+    ('┴', Line::Light, UP | LEFT | RIGHT),
+    ('┼', Line::Light, UP | DOWN | LEFT | RIGHT),
+    ('╭', Line::Light, DOWN | RIGHT),
+    ('╮', Line::Light, DOWN | LEFT),
+    ('╰', Line::Light, UP | RIGHT),
+    ('╯', Line::Light, UP | LEFT),
+    ('━', Line::Heavy, LEFT | RIGHT),
+    ('┃', Line::Heavy, UP | DOWN),
+    ('┏', Line::Heavy, DOWN | RIGHT),
+    ('┓', Line::Heavy, DOWN | LEFT),
+    ('┗', Line::Heavy, UP | RIGHT),
+    ('┛', Line::Heavy, UP | LEFT),
+    ('┣', Line::Heavy, UP | DOWN | RIGHT),
+    ('┫', Line::Heavy, UP | DOWN | LEFT),
+    ('┳', Line::Heavy, DOWN | LEFT | RIGHT),
+    ('┻', Line::Heavy, UP | LEFT | RIGHT),
+    ('╋', Line::Heavy, UP | DOWN | LEFT | RIGHT),
+    ('═', Line::Double, LEFT | RIGHT),
+    ('║', Line::Double, UP | DOWN),
+    ('╔', Line::Double, DOWN | RIGHT),
+    ('╗', Line::Double, DOWN | LEFT),
+    ('╚', Line::Double, UP | RIGHT),
+    ('╝', Line::Double, UP | LEFT),
+    ('╠', Line::Double, UP | DOWN | RIGHT),
+    ('╣', Line::Double, UP | DOWN | LEFT),
+    ('╦', Line::Double, DOWN | LEFT | RIGHT),
+    ('╩', Line::Double, UP | LEFT | RIGHT),
+    ('╬', Line::Double, UP | DOWN | LEFT | RIGHT),
+    ('-', Line::Ascii, LEFT | RIGHT),
+    ('|', Line::Ascii, UP | DOWN),
+    ('+', Line::Ascii, UP | DOWN | LEFT | RIGHT),
+];
+
+fn parse(glyph: char) -> Option<(Line, u8)> {
+    GLYPHS
+        .iter()
+        .find(|(char, _, _)| *char == glyph)
+        .map(|(_, line, arms)| (*line, *arms))
+}
+
+fn glyph(line: Line, arms: u8) -> char {
+    if line == Line::Ascii {
+        return match arms {
+            arms if arms == LEFT | RIGHT => '-',
+            arms if arms == UP | DOWN => '|',
+            _ => '+',
+        };
+    }
+    GLYPHS
+        .iter()
+        .find(|(_, l, a)| *l == line && *a == arms)
+        .map(|(char, _, _)| *char)
+        .unwrap_or(' ')
+}
+
+/// join two glyphs drawn on the same cell, text wins over a glyph
+/// and the line of the first glyph is kept
+pub(crate) fn merge_glyph(a: char, b: char) -> char {
+    if a == b || b == ' ' {
+        return a;
+    }
+    if a == ' ' {
+        return b;
+    }
+    match (parse(a), parse(b)) {
+        (Some((line, arms_a)), Some((_, arms_b))) => glyph(line, arms_a | arms_b),
+        (Some(_), None) => b,
+        (None, _) => a,
+    }
+}
+
+/// draw the glyph of an outer frame over a cell of the inner element,
+/// the text and the spaces of the outer frame are kept
+pub(crate) fn merge_over(outer: char, inner: char) -> char {
+    match parse(outer) {
+        Some(_) => merge_glyph(outer, inner),
+        None => outer,
+    }
+}
+
+/// draw the edge of an outer frame over a line of the inner element
+pub(crate) fn merge_line_over(outer: &str, inner: &str) -> String {
+    let width = display_width(outer).min(display_width(inner));
+    merge_cells(outer, inner, width, merge_over)
+}
+
+/// join two lines drawn on the same row, both lines are plain text
+pub(crate) fn merge_line(a: &str, b: &str) -> String {
+    let width = display_width(a).max(display_width(b));
+    merge_cells(a, b, width, merge_glyph)
+}
+
+/// join the last cell of a line with the first cell of the next one,
+/// a wide grapheme on one side takes the cell of the other side
+pub(crate) fn merge_edge(left: &mut String, right: &str) {
+    let last = left.graphemes(true).next_back().unwrap_or(" ").to_string();
+    left.truncate(left.len().saturating_sub(last.len()));
+    let first = right.graphemes(true).next().unwrap_or(" ");
+    let cell = match (grapheme_width(&last), grapheme_width(first)) {
+        (2, 2) => last + " ",
+        (2, _) => last,
+        (_, 2) => first.to_string(),
+        _ => merge_grapheme(&last, first, merge_glyph),
+    };
+    left.push_str(&cell);
+    left.push_str(&right[first.len().min(right.len())..]);
+}
+
+/// join two lines cell by cell over width columns, a wide grapheme takes two cells,
+/// and a wide grapheme which loses its first cell becomes a space
+fn merge_cells(a: &str, b: &str, width: usize, merge: fn(char, char) -> char) -> String {
+    let (a, b) = (cells(a), cells(b));
+    let mut output = String::new();
+    let mut column = 0;
+    while column < width {
+        let x = a.get(column).copied().flatten().unwrap_or(" ");
+        let y = b.get(column).copied().flatten().unwrap_or(" ");
+        let cell = merge_grapheme(x, y, merge);
+        column += grapheme_width(&cell).max(1);
+        output.push_str(&cell);
+    }
+    output
+}
+
+/// join two graphemes drawn on the same cell, a grapheme which is not one char
+/// is text, which a glyph never joins
+fn merge_grapheme(x: &str, y: &str, merge: fn(char, char) -> char) -> String {
+    let (glyph_x, glyph_y) = (single(x).unwrap_or('\0'), single(y).unwrap_or('\0'));
+    match merge(glyph_x, glyph_y) {
+        merged if merged == glyph_x => x.to_string(),
+        merged if merged == glyph_y => y.to_string(),
+        merged => merged.to_string(),
+    }
+}
+
+/// the graphemes of a plain line by column, none for the second column of a wide one
+fn cells(line: &str) -> Vec<Option<&str>> {
+    let mut cells = vec![];
+    for grapheme in line.graphemes(true) {
+        cells.push(Some(grapheme));
+        if grapheme_width(grapheme) == 2 {
+            cells.push(None);
+        }
+    }
+    cells
+}
+
+/// the char of a grapheme made of one char
+fn single(grapheme: &str) -> Option<char> {
+    let mut chars = grapheme.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+pub(crate) struct Glyphs {
+    pub horizontal: char,
+    pub vertical: char,
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+}
+
+impl BorderStyle {
+    pub(crate) fn glyphs(self) -> Glyphs {
+        let [
+            horizontal,
+            vertical,
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+        ] = match self {
+            BorderStyle::Single => ['─', '│', '┌', '┐', '└', '┘'],
+            BorderStyle::Double => ['═', '║', '╔', '╗', '╚', '╝'],
+            BorderStyle::Rounded => ['─', '│', '╭', '╮', '╰', '╯'],
+            BorderStyle::Heavy => ['━', '┃', '┏', '┓', '┗', '┛'],
+            BorderStyle::Ascii => ['-', '|', '+', '+', '+', '+'],
+        };
+        Glyphs {
+            horizontal,
+            vertical,
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+        }
+    }
+}
+
+/// the columns the title takes in the top edge, with the glyph and spaces around it
+pub(crate) fn title_width(settings: &FrameSettings) -> usize {
+    settings
+        .title
+        .as_ref()
+        .map(|title| display_width(title) + 3)
+        .unwrap_or(0)
+}
+
+/// the top edge of a frame which takes width columns
+pub(crate) fn top_edge(settings: &FrameSettings, width: usize) -> String {
+    let glyphs = settings.style.glyphs();
+    let inner = width.saturating_sub(2);
+    let mut output = String::new();
+    output.push(glyphs.top_left);
+    match &settings.title {
+        Some(title) => {
+            output.push(glyphs.horizontal);
+            output.push(' ');
+            output.push_str(title);
+            output.push(' ');
+            output.extend(std::iter::repeat_n(
+                glyphs.horizontal,
+                inner.saturating_sub(title_width(settings)),
+            ));
+        }
+        None => output.extend(std::iter::repeat_n(glyphs.horizontal, inner)),
+    }
+    output.push(glyphs.top_right);
+    output
+}
+
+/// the bottom edge of a frame which takes width columns
+pub(crate) fn bottom_edge(settings: &FrameSettings, width: usize) -> String {
+    let glyphs = settings.style.glyphs();
+    let mut output = String::new();
+    output.push(glyphs.bottom_left);
+    output.extend(std::iter::repeat_n(
+        glyphs.horizontal,
+        width.saturating_sub(2),
+    ));
+    output.push(glyphs.bottom_right);
+    output
+}
+
+#[test]
+fn tst_merge_glyph() {
+    use crate::elements::CliElement;
+    use crate::layout::{Alignment, RowSettings};
+    assert_eq!(merge_glyph('┐', '┌'), '┬');
+    assert_eq!(merge_glyph('┘', '│'), '┤');
+    assert_eq!(merge_glyph('└', '┌'), '├');
+    assert_eq!(merge_glyph('┃', '├'), '┣');
+    assert_eq!(merge_glyph('+', '|'), '+');
+    assert_eq!(merge_glyph('─', 'T'), 'T');
+    assert_eq!(merge_line("└──┘", "┌──┐"), "├──┤");
+    assert_eq!(merge_line("─日─", "┬──┬"), "┬日┬");
+    assert_eq!(merge_line("─日─", "┬─本"), "┬日─");
+    let mut left = "│日".to_string();
+    merge_edge(&mut left, "│x│");
+    assert_eq!(left, "│日x│");
+    let frame = |text: &str| {
+        CliElement::print_frame(
+            CliElement::print_single(&[text], Alignment::Left),
+            FrameSettings::default(),
+        )
+    };
+    let joined = CliElement::print_frame(
+        CliElement::Row {
+            inner: vec![frame("abcdefg"), frame("efgh")],
+            settings: Some(RowSettings::default()),
+        },
+        FrameSettings {
+            title: Some("日本".to_string()),
+            padding: 0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        joined.render_lines(),
+        vec!["┌─ 日本 ┬────┐", "│abcdefg│efgh│", "└───────┴────┘"]
+    );
+}
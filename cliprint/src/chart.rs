@@ -0,0 +1,171 @@
+//! series of numbers drawn as a sparkline or as a bar chart
+//! ```
+//! use cliprint::chart::sparkline;
+//! use cliprint::layout::SparklineSettings;
+//! let load = [0.5, 1.0, 2.5, 4.0, 3.0, 0.5];
+//! assert_eq!(sparkline(&load, &SparklineSettings::default()).plain_text(), "▁▂▅█▆▁");
+//! ```
+use crate::gauge::{bar, threshold_style};
+use crate::layout::{Alignment, BarChartSettings, SparklineSettings};
+use crate::style::{Line, Span};
+use crate::text::{display_width, pad};
+
+/// the bars of a sparkline, from the lowest to the highest
+const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// a bar for every value, a value which is not finite is a space
+pub fn sparkline(values: &[f64], settings: &SparklineSettings) -> Line {
+    let finite = values.iter().copied().filter(|value| value.is_finite());
+    let min = settings
+        .min
+        .unwrap_or_else(|| finite.clone().fold(f64::INFINITY, f64::min));
+    let max = settings
+        .max
+        .unwrap_or_else(|| finite.fold(f64::NEG_INFINITY, f64::max));
+    let text: String = values
+        .iter()
+        .map(|value| {
+            if !value.is_finite() {
+                return ' ';
+            }
+            let ratio = if max > min {
+                ((value - min) / (max - min)).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            LEVELS[(ratio * 7.0).round() as usize]
+        })
+        .collect();
+    Line::new(vec![Span::new(text, settings.style)])
+}
+
+/// the value as it is shown next to its bar, whole numbers without decimals
+fn format_value(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{value:.0}")
+    } else {
+        format!("{value:.1}")
+    }
+}
+
+/// a line for every bar, with the label before it,
+/// and two lines of axis under the bars when the settings ask for them
+/// ```
+/// use cliprint::chart::bar_chart;
+/// use cliprint::layout::BarChartSettings;
+/// let cores = [("cpu0".to_string(), 50.0), ("cpu1".to_string(), 100.0)];
+/// let settings = BarChartSettings {
+///     width: 4,
+///     max: Some(100.0),
+///     axis: true,
+///     ..Default::default()
+/// };
+/// let lines: Vec<String> = bar_chart(&cores, &settings)
+///     .iter()
+///     .map(|line| line.plain_text())
+///     .collect();
+/// assert_eq!(
+///     lines,
+///     vec![
+///         "cpu0 │██    50",
+///         "cpu1 │████ 100",
+///         "     └────",
+///         "      0 100",
+///     ]
+/// );
+/// ```
+pub fn bar_chart(bars: &[(String, f64)], settings: &BarChartSettings) -> Vec<Line> {
+    let max = settings.max.unwrap_or_else(|| {
+        bars.iter()
+            .map(|(_, value)| *value)
+            .filter(|value| value.is_finite())
+            .fold(0.0, f64::max)
+    });
+    let label_width = bars
+        .iter()
+        .map(|(label, _)| display_width(label))
+        .max()
+        .unwrap_or(0);
+    let values: Vec<String> = bars.iter().map(|(_, value)| format_value(*value)).collect();
+    let value_width = values
+        .iter()
+        .map(|value| display_width(value))
+        .max()
+        .unwrap_or(0);
+    let mut lines = vec![];
+    for ((label, value), text) in bars.iter().zip(&values) {
+        let ratio = if max > 0.0 {
+            (value / max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (fill, used) = bar(
+            ratio * settings.width as f64,
+            settings.fill,
+            settings.smooth,
+        );
+        let mut spans = vec![
+            settings
+                .label_style
+                .paint(pad(label, label_width, Alignment::Left)),
+            Span::plain(if settings.axis { " │" } else { " " }),
+            Span::new(fill, threshold_style(ratio, &settings.thresholds)),
+        ];
+        if settings.values {
+            spans.push(Span::plain(format!(
+                "{} {}",
+                " ".repeat(settings.width - used),
+                pad(text, value_width, Alignment::Right)
+            )));
+        }
+        spans.retain(|span| !span.text.is_empty());
+        lines.push(Line::new(spans));
+    }
+    if settings.axis {
+        let indent = " ".repeat(label_width + 1);
+        lines.push(Line::from(format!(
+            "{indent}└{}",
+            "─".repeat(settings.width)
+        )));
+        let scale = format_value(max);
+        lines.push(Line::from(format!(
+            "{indent} 0{}",
+            pad(
+                &scale,
+                settings
+                    .width
+                    .saturating_sub(1)
+                    .max(display_width(&scale) + 1),
+                Alignment::Right
+            )
+        )));
+    }
+    lines
+}
+
+#[test]
+fn tst_chart() {
+    use crate::style::{Color, Style};
+    let settings = SparklineSettings {
+        min: Some(0.0),
+        max: Some(10.0),
+        style: Color::Cyan.normal(),
+    };
+    let line = sparkline(&[0.0, 5.0, f64::NAN, 20.0], &settings);
+    assert_eq!(line.plain_text(), "▁▅ █");
+    assert_eq!(line.spans[0].style, Color::Cyan.normal());
+    assert_eq!(
+        sparkline(&[3.0, 3.0], &Default::default()).plain_text(),
+        "▁▁"
+    );
+    let settings = BarChartSettings {
+        width: 3,
+        values: false,
+        thresholds: vec![(0.0, Style::new()), (0.8, Color::Red.normal())],
+        ..Default::default()
+    };
+    let bars = [("a".to_string(), 1.0), ("bb".to_string(), 4.0)];
+    let lines = bar_chart(&bars, &settings);
+    assert_eq!(lines[0].plain_text(), "a  ▊");
+    assert_eq!(lines[1].spans[2], Color::Red.paint("███"));
+}
@@ -0,0 +1,195 @@
+//! gradients, rainbows and colors for every character, made with truecolors which
+//! are downgraded when the element is drawn on a terminal without them
+//! ```
+//! use cliprint::colorize::colorize;
+//! use cliprint::layout::{Colorize, GradientDirection};
+//! use cliprint::style::{Color, Line};
+//! let lines = colorize(
+//!     &[Line::from("abc")],
+//!     &Colorize::Gradient {
+//!         stops: vec![Color::Rgb(255, 0, 0), Color::Rgb(0, 0, 255)],
+//!         direction: GradientDirection::Horizontal,
+//!     },
+//! );
+//! assert_eq!(
+//!     lines[0].render(),
+//!     "\x1b[38;2;255;0;0ma\x1b[0m\x1b[38;2;128;0;128mb\x1b[0m\x1b[38;2;0;0;255mc\x1b[0m"
+//! );
+//! ```
+use std::f64::consts::PI;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::layout::{Colorize, GradientDirection};
+use crate::style::{Color, Line, Span, Style};
+use crate::text::{Token, grapheme_width, tokens};
+
+/// the lines with the colors of every character, the other parts of the styles are kept
+pub fn colorize(lines: &[Line], colorize: &Colorize) -> Vec<Line> {
+    let width = lines.iter().map(Line::width).max().unwrap_or(0);
+    let size = (width, lines.len());
+    lines
+        .iter()
+        .enumerate()
+        .map(|(y, line)| colorize_line(line, colorize, y, size))
+        .collect()
+}
+
+/// the same as [colorize] for the line y of lines which take the size,
+/// the columns and the lines
+pub(crate) fn colorize_line(
+    line: &Line,
+    colorize: &Colorize,
+    y: usize,
+    size: (usize, usize),
+) -> Line {
+    let mut spans: Vec<Span> = vec![];
+    let mut x = 0;
+    for span in &line.spans {
+        for token in tokens(&span.text) {
+            match token {
+                Token::Escape(escape) => push(&mut spans, escape, span.style),
+                Token::Text(text) => {
+                    for grapheme in text.graphemes(true) {
+                        let color = if grapheme.trim().is_empty() {
+                            None
+                        } else {
+                            color_at(colorize, grapheme, (x, y), size)
+                        };
+                        let style = match color {
+                            Some(color) => span.style.fg(color),
+                            None => span.style,
+                        };
+                        push(&mut spans, grapheme, style);
+                        x += grapheme_width(grapheme);
+                    }
+                }
+            }
+        }
+    }
+    Line::new(spans)
+}
+
+/// the color of the character at the column and the line
+fn color_at(
+    colorize: &Colorize,
+    grapheme: &str,
+    (x, y): (usize, usize),
+    (width, height): (usize, usize),
+) -> Option<Color> {
+    let ratio = |position: usize, len: usize| {
+        if len > 1 {
+            position as f64 / (len - 1) as f64
+        } else {
+            0.0
+        }
+    };
+    match colorize {
+        Colorize::Gradient { stops, direction } => {
+            let position = match direction {
+                GradientDirection::Horizontal => ratio(x, width),
+                GradientDirection::Vertical => ratio(y, height),
+                GradientDirection::Diagonal => (ratio(x, width) + ratio(y, height)) / 2.0,
+            };
+            gradient(stops, position)
+        }
+        Colorize::Rainbow {
+            frequency,
+            spread,
+            offset,
+        } => {
+            let step = frequency * (offset + y as f64 + x as f64 / spread.max(f64::EPSILON));
+            let channel = |phase: f64| ((step + phase).sin() * 127.0 + 128.0) as u8;
+            Some(Color::Rgb(
+                channel(0.0),
+                channel(2.0 * PI / 3.0),
+                channel(4.0 * PI / 3.0),
+            ))
+        }
+        Colorize::CharMap(colors) => {
+            let mut chars = grapheme.chars();
+            let char = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            colors
+                .iter()
+                .find(|(key, _)| *key == char)
+                .map(|(_, color)| *color)
+        }
+    }
+}
+
+/// the color at the position between the stops, from 0.0 to 1.0
+fn gradient(stops: &[Color], position: f64) -> Option<Color> {
+    let last = stops.len().checked_sub(1)?;
+    let position = position.clamp(0.0, 1.0) * last as f64;
+    let index = (position.floor() as usize).min(last.saturating_sub(1));
+    let (from, to) = (stops[index].to_rgb(), stops[(index + 1).min(last)].to_rgb());
+    let part = position - index as f64;
+    let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * part).round() as u8;
+    Some(Color::Rgb(
+        mix(from.0, to.0),
+        mix(from.1, to.1),
+        mix(from.2, to.2),
+    ))
+}
+
+/// add the text to the last span when it has the same style
+fn push(spans: &mut Vec<Span>, text: &str, style: Style) {
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(text),
+        _ => spans.push(Span::new(text, style)),
+    }
+}
+
+#[test]
+fn tst_colorize() {
+    let lines = [Line::from("a b"), Line::from("\x1b[1mcd\x1b[0m")];
+    let vertical = colorize(
+        &lines,
+        &Colorize::Gradient {
+            stops: vec![Color::Black, Color::White, Color::Rgb(0, 0, 0)],
+            direction: GradientDirection::Vertical,
+        },
+    );
+    assert_eq!(
+        vertical[0].spans,
+        vec![
+            Color::Rgb(0, 0, 0).paint("a"),
+            Span::plain(" "),
+            Color::Rgb(0, 0, 0).paint("b")
+        ]
+    );
+    assert_eq!(
+        vertical[1].spans,
+        vec![Color::Rgb(0, 0, 0).bold().paint("cd")]
+    );
+    let diagonal = colorize(
+        &lines,
+        &Colorize::Gradient {
+            stops: vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 100, 0)],
+            direction: GradientDirection::Diagonal,
+        },
+    );
+    assert_eq!(diagonal[0].spans[0].style.fg, Some(Color::Rgb(0, 0, 0)));
+    assert_eq!(diagonal[1].spans[1].style.fg, Some(Color::Rgb(150, 75, 0)));
+    let mapped = colorize(
+        &lines,
+        &Colorize::CharMap(vec![('b', Color::Red), ('c', Color::Blue)]),
+    );
+    assert_eq!(
+        mapped[0].spans,
+        vec![Span::plain("a "), Color::Red.paint("b")]
+    );
+    assert_eq!(
+        mapped[1].spans,
+        vec![
+            Color::Blue.bold().paint("c"),
+            Style::new().bold().paint("d")
+        ]
+    );
+    let rainbow = colorize(&lines, &Colorize::rainbow());
+    assert_eq!(rainbow[0].spans[0].style.fg, Some(Color::Rgb(128, 237, 18)));
+    assert_eq!(rainbow[0].plain_text(), "a b");
+}
@@ -1,9 +1,27 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
 
-use strfmt::strfmt;
-
-use crate::layout::{Alignment, RowSettings};
+use crate::grid::GridCell;
+use crate::html::render_html;
+use crate::image::Image;
+use crate::layout::{
+    Alignment, BarChartSettings, Colorize, FrameSettings, GaugeSettings, GridSettings,
+    HtmlSettings, ImageProtocol, Overflow, RowSettings, SparklineSettings, SvgSettings,
+    TableSettings,
+};
+#[cfg(test)]
+use crate::layout::{BorderStyle, VerticalAlignment};
+use crate::measure::Measured;
+use crate::overlay::Layer;
+use crate::producer::{Producer, resolve};
+use crate::style::{Line, downgrade_line};
+use crate::svg::render_svg;
+use crate::template::{Template, TemplateData};
+use crate::terminal::{ColorSupport, terminal_width};
 
+#[cfg(feature = "color")]
+use crate::style::Style;
 #[cfg(feature = "color")]
 use nu_ansi_term::Color;
 #[cfg(feature = "nightly")]
@@ -19,39 +37,97 @@ use std::pin::Pin;
 /// ```
 /// Other one should build with impl
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CliElement {
     Row {
         inner: Vec<CliElement>,
+        #[cfg_attr(feature = "serde", serde(default))]
         settings: Option<RowSettings>,
     },
     Column {
         inner: Vec<CliElement>,
     },
     Single {
-        inner: Vec<String>,
+        inner: Vec<Line>,
+        #[cfg_attr(feature = "serde", serde(default))]
         layout: Alignment,
     },
     EmptyBlock,
-}
-
-fn init_matrix(height: usize) -> Vec<String> {
-    let mut output = vec![];
-    for _ in 0..height {
-        output.push(String::new());
-    }
-    output
-}
-
-fn init_string_with_width(width: usize) -> String {
-    let mut output = String::new();
-    for _ in 0..width {
-        output.push(' ');
-    }
-    output
+    /// the inner element takes at most max_width columns,
+    /// a wider line is handled as the overflow says
+    Constrained {
+        inner: Box<CliElement>,
+        max_width: usize,
+        #[cfg_attr(feature = "serde", serde(default))]
+        overflow: Overflow,
+    },
+    /// a border around the inner element, frames which touch share their border
+    Frame {
+        inner: Box<CliElement>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: FrameSettings,
+    },
+    /// keys and values, the separators are lined up in one column,
+    /// a key or a value with line breaks takes many lines
+    Table {
+        rows: Vec<(String, String)>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: TableSettings,
+    },
+    /// a picture drawn with a graphics protocol over width columns and height rows
+    Image {
+        image: Image,
+        #[cfg_attr(feature = "serde", serde(default))]
+        protocol: ImageProtocol,
+        width: usize,
+        height: usize,
+    },
+    /// a bar which is filled by the ratio, from 0.0 to 1.0
+    Gauge {
+        ratio: f64,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: GaugeSettings,
+    },
+    /// a bar of one cell for every value
+    Sparkline {
+        values: Vec<f64>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: SparklineSettings,
+    },
+    /// a line with a label and a bar for every value
+    BarChart {
+        bars: Vec<(String, f64)>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: BarChartSettings,
+    },
+    /// the characters of the inner element colored again, see [colorize](crate::colorize)
+    Colorized {
+        inner: Box<CliElement>,
+        colorize: Colorize,
+    },
+    /// cells laid out in columns, see [grid](crate::grid)
+    Grid {
+        cells: Vec<GridCell>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        settings: GridSettings,
+    },
+    /// layers drawn over each other, see [overlay](crate::overlay)
+    Overlay {
+        layers: Vec<Layer>,
+    },
+    /// a template filled with the values of its keys when it is drawn,
+    /// see [template](crate::template)
+    Template {
+        template: Template,
+        data: BTreeMap<String, String>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        layout: Alignment,
+    },
 }
 
 impl CliElement {
-    /// use a matrix to init a CliElement::Single
+    /// use a matrix to init a CliElement::Single,
+    /// the sgr escape sequences in it are read into styles
     /// ```
     /// use cliprint::elements::CliElement;
     /// use cliprint::layout::Alignment;
@@ -61,11 +137,33 @@ impl CliElement {
     pub fn print_single(matrix: &[&str], layout: Alignment) -> Self {
         let mut inner = vec![];
         for mat in matrix {
-            inner.push(mat.to_string());
+            inner.push(Line::from_ansi(mat));
         }
         CliElement::Single { inner, layout }
     }
 
+    /// same as print_single, but with styled lines
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// use cliprint::style::{Color, Line, Span};
+    /// let a = CliElement::print_single_styled(
+    ///     vec![Line::from(vec![
+    ///         Color::Cyan.bold().paint("OS"),
+    ///         Span::plain(": ArchLinux"),
+    ///     ])],
+    ///     Alignment::Left,
+    /// );
+    /// assert_eq!(a.width(), 13);
+    /// ```
+    #[must_use]
+    pub fn print_single_styled(lines: Vec<Line>, layout: Alignment) -> Self {
+        CliElement::Single {
+            inner: lines,
+            layout,
+        }
+    }
+
     /// same as print_single, but with string
     /// ```
     /// use cliprint::elements::CliElement;
@@ -88,18 +186,152 @@ impl CliElement {
         color: Color,
         is_bold: bool,
     ) -> Self {
-        let matrix: Vec<String> = matrix
+        let style: Style = if is_bold {
+            color.bold().into()
+        } else {
+            color.normal().into()
+        };
+        let lines = matrix
             .lines()
-            .map(|line| {
-                if is_bold {
-                    color.bold().paint(line).to_string()
-                } else {
-                    color.paint(line).to_string()
-                }
-            })
+            .map(|line| Line::from(style.paint(line)))
             .collect();
-        let matrix: Vec<&str> = matrix.iter().map(|s| s.as_str()).collect();
-        Self::print_single(&matrix, layout)
+        Self::print_single_styled(lines, layout)
+    }
+
+    /// the template with the values of its keys taken from the data, such as a map
+    /// or a json value, the template is filled when the element is drawn
+    /// ```
+    /// use std::collections::HashMap;
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// use cliprint::template::Template;
+    /// let template = Template::parse("{name}\n{kernel|unknown:>10}").unwrap();
+    /// let data = HashMap::from([("name", "marine")]);
+    /// let mut a = CliElement::print_template(template, &data, Alignment::Left);
+    /// assert_eq!(a.render_lines(), vec!["marine    ", "   unknown"]);
+    /// if let CliElement::Template { data, .. } = &mut a {
+    ///     data.insert("kernel".to_string(), "6.17".to_string());
+    /// }
+    /// assert_eq!(a.render_lines(), vec!["marine    ", "      6.17"]);
+    /// ```
+    #[must_use]
+    pub fn print_template(template: Template, data: &impl TemplateData, layout: Alignment) -> Self {
+        let data = template
+            .keys()
+            .filter_map(|key| Some((key.to_string(), data.value(key)?)))
+            .collect();
+        CliElement::Template {
+            template,
+            data,
+            layout,
+        }
+    }
+
+    /// a picture which takes width columns, the rows keep the shape of the picture
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::image::Image;
+    /// use cliprint::layout::{Alignment, ImageProtocol, RowSettings};
+    /// let logo = Image::from_rgba(2, 2, vec![255; 16]).unwrap();
+    /// let a = CliElement::Row {
+    ///     inner: vec![
+    ///         CliElement::print_image(logo, ImageProtocol::Kitty, 4),
+    ///         CliElement::print_single(&["OS: Arch", "WM: sway"], Alignment::Left),
+    ///     ],
+    ///     settings: Some(RowSettings { spacing: 1, ..Default::default() }),
+    /// };
+    /// let lines = a.render_lines();
+    /// assert!(lines[0].starts_with(" \x1b_Ga=T,f=32,s=2,v=2,c=4,r=2,"));
+    /// assert!(lines[0].ends_with("\x1b\\     OS: Arch "));
+    /// assert_eq!(lines[1], "      WM: sway ");
+    /// ```
+    #[must_use]
+    pub fn print_image(image: Image, protocol: ImageProtocol, width: usize) -> Self {
+        let height = image.rows_for(width);
+        CliElement::Image {
+            image,
+            protocol,
+            width,
+            height,
+        }
+    }
+
+    /// a bar which shows how full something is
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::GaugeSettings;
+    /// let memory = CliElement::print_gauge(
+    ///     0.25,
+    ///     GaugeSettings {
+    ///         width: 8,
+    ///         fill: '=',
+    ///         empty: ' ',
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// assert_eq!(memory.width(), 13);
+    /// assert_eq!(
+    ///     memory.render_lines(),
+    ///     vec!["\x1b[32m==\x1b[0m\x1b[2m      \x1b[0m  25%"]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn print_gauge(ratio: f64, settings: GaugeSettings) -> Self {
+        CliElement::Gauge { ratio, settings }
+    }
+
+    /// a sparkline of the values, see [sparkline](crate::chart::sparkline)
+    #[must_use]
+    pub fn print_sparkline(values: Vec<f64>, settings: SparklineSettings) -> Self {
+        CliElement::Sparkline { values, settings }
+    }
+
+    /// a bar chart of the values, see [bar_chart](crate::chart::bar_chart)
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::BarChartSettings;
+    /// let a = CliElement::print_bar_chart(
+    ///     vec![("cpu0".to_string(), 12.5), ("cpu1".to_string(), 3.0)],
+    ///     BarChartSettings {
+    ///         width: 5,
+    ///         max: Some(25.0),
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// assert_eq!((a.width(), a.height()), (15, 2));
+    /// ```
+    #[must_use]
+    pub fn print_bar_chart(bars: Vec<(String, f64)>, settings: BarChartSettings) -> Self {
+        CliElement::BarChart { bars, settings }
+    }
+
+    /// color every character of the element, a gradient goes over the whole element
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::{Alignment, Colorize};
+    /// use cliprint::terminal::ColorSupport;
+    /// let logo = CliElement::print_single(&["##", "##"], Alignment::Left);
+    /// let a = CliElement::print_colorized(logo, Colorize::rainbow());
+    /// assert_eq!(a.render_lines_with(ColorSupport::None), vec!["##", "##"]);
+    /// ```
+    #[must_use]
+    pub fn print_colorized(element: CliElement, colorize: Colorize) -> Self {
+        CliElement::Colorized {
+            inner: Box::new(element),
+            colorize,
+        }
+    }
+
+    /// lay out the cells in the columns of the settings
+    #[must_use]
+    pub fn print_grid(cells: Vec<GridCell>, settings: GridSettings) -> Self {
+        CliElement::Grid { cells, settings }
+    }
+
+    /// the layers over each other, the later ones over the earlier ones
+    #[must_use]
+    pub fn print_overlay(layers: Vec<Layer>) -> Self {
+        CliElement::Overlay { layers }
     }
 
     #[must_use]
@@ -107,6 +339,54 @@ impl CliElement {
         CliElement::EmptyBlock
     }
 
+    /// limit the width of the element
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::{Alignment, Overflow};
+    /// let gpu = CliElement::print_single(&["GPU: NVIDIA GeForce RTX 4090"], Alignment::Left);
+    /// let a = CliElement::print_constrained(gpu, 12, Overflow::Ellipsis);
+    /// assert_eq!(a.render_lines(), vec!["GPU: NVIDIA…"]);
+    /// ```
+    #[must_use]
+    pub fn print_constrained(element: CliElement, max_width: usize, overflow: Overflow) -> Self {
+        CliElement::Constrained {
+            inner: Box::new(element),
+            max_width,
+            overflow,
+        }
+    }
+
+    /// limit the width of the element to the width of the terminal,
+    /// the element is kept as it is when the width is unknown
+    #[must_use]
+    pub fn print_fit_terminal(element: CliElement, overflow: Overflow) -> Self {
+        match terminal_width() {
+            Some(max_width) => Self::print_constrained(element, max_width, overflow),
+            None => element,
+        }
+    }
+
+    /// draw a border around the element
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::{Alignment, FrameSettings};
+    /// let a = CliElement::print_frame(
+    ///     CliElement::print_single(&["sss"], Alignment::Left),
+    ///     FrameSettings {
+    ///         title: Some("a".to_string()),
+    ///         ..Default::default()
+    ///     },
+    /// );
+    /// assert_eq!(a.render_lines(), vec!["┌─ a ┐", "│sss │", "└────┘"]);
+    /// ```
+    #[must_use]
+    pub fn print_frame(element: CliElement, settings: FrameSettings) -> Self {
+        CliElement::Frame {
+            inner: Box::new(element),
+            settings,
+        }
+    }
+
     #[cfg(not(feature = "nightly"))]
     #[must_use]
     pub fn print_column(elements: impl Iterator<Item = CliElement>) -> Self {
@@ -127,6 +407,50 @@ impl CliElement {
         }
     }
 
+    /// the elements of the producers in a column, the producers are driven at the
+    /// same time, see [producer](crate::producer)
+    #[must_use]
+    pub fn print_column_async(producers: Vec<Producer>) -> Self {
+        CliElement::Column {
+            inner: resolve(producers),
+        }
+    }
+
+    /// the elements of the producers in a row, the producers are driven at the same time
+    #[must_use]
+    pub fn print_row_async(producers: Vec<Producer>, settings: Option<RowSettings>) -> Self {
+        CliElement::Row {
+            inner: resolve(producers),
+            settings,
+        }
+    }
+
+    /// a table of keys and values
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::TableSettings;
+    /// let a = CliElement::print_table(
+    ///     vec![
+    ///         ("OS".to_string(), "ArchLinux".to_string()),
+    ///         ("Kernel".to_string(), "6.3.0".to_string()),
+    ///     ]
+    ///     .into_iter(),
+    ///     None,
+    /// );
+    /// assert_eq!(a.render_lines(), vec!["OS    : ArchLinux", "Kernel: 6.3.0    "]);
+    /// ```
+    #[cfg(not(feature = "nightly"))]
+    #[must_use]
+    pub fn print_table(
+        rows: impl Iterator<Item = (String, String)>,
+        settings: Option<TableSettings>,
+    ) -> Self {
+        CliElement::Table {
+            rows: rows.collect(),
+            settings: settings.unwrap_or_default(),
+        }
+    }
+
     #[cfg(feature = "nightly")]
     #[must_use]
     pub fn print_column<G>(mut generator: G) -> Self
@@ -162,132 +486,214 @@ impl CliElement {
         CliElement::Row { inner, settings }
     }
 
-    fn get_draw_map(&self, draw_width: usize) -> Vec<String> {
-        match self {
-            CliElement::EmptyBlock => {
-                let formatalign = format!("{{content: <{}}}", draw_width);
-                let format_res = strfmt(
-                    &formatalign,
-                    &HashMap::from([("content".to_string(), String::new())]),
-                )
-                .unwrap();
-                vec![format_res]
-            }
-            CliElement::Single { inner, layout } => {
-                let formatalign = match layout {
-                    Alignment::Left => format!("{{content: <{}}}", draw_width),
-                    Alignment::Right => format!("{{content: >{}}}", draw_width),
-                };
-                let mut output = vec![];
-                for inn in inner {
-                    output.push({
-                        strfmt(
-                            &formatalign,
-                            &HashMap::from([("content".to_string(), inn.clone())]),
-                        )
-                        .unwrap()
-                    });
-                }
-                output
-            }
-            CliElement::Column { inner } => {
-                let mut output = vec![];
-                for inn in inner {
-                    output.append(&mut inn.get_draw_map(draw_width));
-                }
-                output
-            }
-            CliElement::Row { inner, settings } => {
-                let spacewidth = settings.and_then(|a| Some(a.spacing)).unwrap_or(0);
-                let spacestring = init_string_with_width(spacewidth);
-                let height = self.height();
-                let mut adjust = init_matrix(height);
-                for item in adjust.iter_mut().take(height) {
-                    item.push_str(&spacestring);
-                }
-                for inn in inner {
-                    let mut inn2 = inn.get_draw_map(inn.width());
-                    let formatalign = format!("{{content: <{}}}", inn.width());
-                    for _ in inn.height()..height {
-                        inn2.push(
-                            strfmt(
-                                &formatalign,
-                                &HashMap::from([("content".to_string(), String::new())]),
-                            )
-                            .unwrap(),
-                        );
-                    }
-                    for index in 0..height {
-                        adjust[index].push_str(&inn2[index]);
-                        adjust[index].push_str(&spacestring);
-                    }
+    /// a table of keys and values, the coroutine returns the settings
+    #[cfg(feature = "nightly")]
+    #[must_use]
+    pub fn print_table<G>(mut generator: G) -> Self
+    where
+        G: Coroutine<Yield = (String, String), Return = Option<TableSettings>> + std::marker::Unpin,
+    {
+        let mut rows = vec![];
+        let settings;
+        loop {
+            match Pin::new(&mut generator).resume(()) {
+                CoroutineState::Yielded(row) => rows.push(row),
+                CoroutineState::Complete(setting) => {
+                    settings = setting.unwrap_or_default();
+                    break;
                 }
-                adjust
             }
         }
+
+        CliElement::Table { rows, settings }
     }
 
+    /// print the element to stdout, with the colors the terminal can show
     pub fn draw(&self) {
-        let map = self.get_draw_map(self.width());
-        for ma in map {
-            println!("{}", ma);
-        }
+        self.write_to_with(&mut io::stdout().lock(), ColorSupport::detect())
+            .expect("failed printing to stdout");
     }
 
-    pub fn width(&self) -> usize {
-        match self {
-            CliElement::Row { inner, settings } => {
-                let mut len = 0;
-                for inn in inner {
-                    len += inn.width();
-                }
-                let spacwidth = settings.and_then(|a| Some(a.spacing)).unwrap_or(0);
-                len += (inner.len() + 1) * spacwidth;
-                len
-            }
-            CliElement::EmptyBlock => 0,
-            CliElement::Column { inner } => {
-                let mut len = 0;
-                for inn in inner {
-                    if inn.width() > len {
-                        len = inn.width();
-                    }
-                }
-                len
-            }
-            CliElement::Single { inner, .. } => {
-                let mut len = 0;
-                for inn in inner {
-                    if inn.len() > len {
-                        len = inn.len();
-                    }
+    /// the lines of the element, every line padded to the width of the element
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// let a = CliElement::print_single(&["sss", "b"], Alignment::Right);
+    /// assert_eq!(a.render_lines(), vec!["sss", "  b"]);
+    /// ```
+    #[must_use]
+    pub fn render_lines(&self) -> Vec<String> {
+        self.lines().collect()
+    }
+
+    /// the lines of the element one at a time, a line is drawn when it is taken, so
+    /// a long output can be paged or piped without keeping it all
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// let a = CliElement::Column {
+    ///     inner: (0..100_000)
+    ///         .map(|index| CliElement::print_single_from_str(&index.to_string(), Alignment::Right))
+    ///         .collect(),
+    /// };
+    /// let mut lines = a.lines().skip(9);
+    /// assert_eq!(lines.next().unwrap(), "    9");
+    /// ```
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        let measured = Measured::new(self);
+        let width = measured.width;
+        measured.into_lines(width)
+    }
+
+    /// the same as [CliElement::lines], the colors are downgraded to the support,
+    /// see [CliElement::render_lines_with]
+    pub fn lines_with(&self, support: ColorSupport) -> impl Iterator<Item = String> + '_ {
+        let measured = match support {
+            ColorSupport::Plain => Measured::plain(self),
+            _ => Some(Measured::new(self)),
+        };
+        measured.into_iter().flat_map(move |measured| {
+            let width = measured.width;
+            measured.into_lines(width).map(move |line| {
+                let line = downgrade_line(&line, support);
+                match support {
+                    ColorSupport::Plain => line.trim_end().to_string(),
+                    _ => line,
                 }
-                len
-            }
+            })
+        })
+    }
+
+    /// the same as [CliElement::render_lines], the colors are downgraded to the support
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// use cliprint::terminal::ColorSupport;
+    /// let a = CliElement::print_single(&["\x1b[38;2;255;0;0mred\x1b[0m"], Alignment::Left);
+    /// assert_eq!(
+    ///     a.render_lines_with(ColorSupport::Ansi256),
+    ///     vec!["\x1b[38;5;196mred\x1b[0m"]
+    /// );
+    /// ```
+    ///
+    /// when it is plain the elements which show only colors, such as pictures and
+    /// lines of colored spaces, are left out, and the spaces at the end of the lines
+    /// are cut
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// use cliprint::terminal::ColorSupport;
+    /// let a = CliElement::Column {
+    ///     inner: vec![
+    ///         CliElement::print_single(&["\x1b[1;36mOS\x1b[0m: Arch"], Alignment::Right),
+    ///         CliElement::print_single(&["\x1b[41m   \x1b[42m   \x1b[0m"], Alignment::Left),
+    ///     ],
+    /// };
+    /// assert_eq!(a.render_lines_with(ColorSupport::Plain), vec!["OS: Arch"]);
+    /// ```
+    #[must_use]
+    pub fn render_lines_with(&self, support: ColorSupport) -> Vec<String> {
+        self.lines_with(support).collect()
+    }
+
+    /// the same as [CliElement::write_to], the colors are downgraded to the support
+    pub fn write_to_with<W: io::Write>(
+        &self,
+        writer: &mut W,
+        support: ColorSupport,
+    ) -> io::Result<()> {
+        for line in self.lines_with(support) {
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush()
+    }
+
+    /// write the element to any writer, every line ends with a newline
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::Alignment;
+    /// let mut buffer = vec![];
+    /// CliElement::print_single(&["sss"], Alignment::Left)
+    ///     .write_to(&mut buffer)
+    ///     .unwrap();
+    /// assert_eq!(buffer, b"sss\n");
+    /// ```
+    pub fn write_to<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        for line in self.lines() {
+            writeln!(writer, "{}", line)?;
         }
+        writer.flush()
+    }
+
+    /// the element as html, see [html](crate::html)
+    /// ```
+    /// use cliprint::elements::CliElement;
+    /// use cliprint::layout::{Alignment, HtmlSettings};
+    /// let a = CliElement::print_single(&["sss", "b"], Alignment::Right);
+    /// assert_eq!(
+    ///     a.render_html(&HtmlSettings::default()),
+    ///     "<pre>sss\n  b</pre>\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn render_html(&self, settings: &HtmlSettings) -> String {
+        let lines: Vec<Line> = self
+            .render_lines()
+            .iter()
+            .map(|line| Line::from_ansi(line))
+            .collect();
+        render_html(&lines, settings)
+    }
+
+    /// write the element as html to any writer
+    pub fn write_html<W: io::Write>(
+        &self,
+        writer: &mut W,
+        settings: &HtmlSettings,
+    ) -> io::Result<()> {
+        writer.write_all(self.render_html(settings).as_bytes())?;
+        writer.flush()
+    }
+
+    /// the element drawn to svg, see [svg](crate::svg)
+    #[must_use]
+    pub fn render_svg(&self, settings: &SvgSettings) -> String {
+        let lines: Vec<Line> = self
+            .render_lines()
+            .iter()
+            .map(|line| Line::from_ansi(line))
+            .collect();
+        render_svg(&lines, settings)
+    }
+
+    /// write the element as svg to any writer
+    pub fn write_svg<W: io::Write>(
+        &self,
+        writer: &mut W,
+        settings: &SvgSettings,
+    ) -> io::Result<()> {
+        writer.write_all(self.render_svg(settings).as_bytes())?;
+        writer.flush()
+    }
+
+    /// the columns the element takes
+    pub fn width(&self) -> usize {
+        Measured::new(self).width
     }
 
+    /// the lines the element takes
     pub fn height(&self) -> usize {
-        match self {
-            CliElement::Row { inner, .. } => {
-                let mut len = 0;
-                for inn in inner {
-                    if inn.height() > len {
-                        len = inn.height();
-                    }
-                }
-                len
-            }
-            CliElement::EmptyBlock => 1,
-            CliElement::Column { inner } => {
-                let mut len = 0;
-                for inn in inner {
-                    len += inn.height();
-                }
-                len
-            }
-            CliElement::Single { inner, .. } => inner.len(),
+        Measured::new(self).height
+    }
+}
+
+/// the same as [CliElement::draw], so `to_string` gives the whole output
+impl fmt::Display for CliElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{}", line)?;
         }
+        Ok(())
     }
 }
 
@@ -307,6 +713,255 @@ fn tst_len() {
     assert_eq!(test.width(), 6);
 }
 
+#[test]
+fn tst_unicode_width() {
+    let test = CliElement::Row {
+        inner: vec![
+            CliElement::print_single(&["\x1b[1;36m名前\x1b[0m", "sss"], Alignment::Left),
+            CliElement::print_single(&["ab"], Alignment::Right),
+        ],
+        settings: Some(RowSettings {
+            spacing: 1,
+            ..Default::default()
+        }),
+    };
+    assert_eq!(test.width(), 9);
+    assert_eq!(
+        test.render_lines(),
+        vec![" \x1b[1;36m名前\x1b[0m ab ", " sss     "]
+    );
+}
+
+#[test]
+fn tst_alignment() {
+    let test = CliElement::Row {
+        inner: vec![
+            CliElement::print_single(&["a", "bbb", "c", "d", "e"], Alignment::Center),
+            CliElement::print_single(&["x"], Alignment::Left),
+        ],
+        settings: Some(RowSettings {
+            spacing: 0,
+            vertical: VerticalAlignment::Middle,
+        }),
+    };
+    assert_eq!(
+        test.render_lines(),
+        vec![" a  ", "bbb ", " c x", " d  ", " e  "]
+    );
+    let test = CliElement::Row {
+        inner: vec![
+            CliElement::print_single(&["a", "b"], Alignment::Left),
+            CliElement::print_single(&["x"], Alignment::Left),
+        ],
+        settings: Some(RowSettings {
+            spacing: 0,
+            vertical: VerticalAlignment::Bottom,
+        }),
+    };
+    assert_eq!(test.render_lines(), vec!["a ", "bx"]);
+}
+
+#[test]
+fn tst_constrained() {
+    let info = CliElement::Column {
+        inner: vec![
+            CliElement::print_single(
+                &["\x1b[1;36mCPU\x1b[0m: AMD Ryzen 7 5800H"],
+                Alignment::Left,
+            ),
+            CliElement::print_single(&["WM: sway"], Alignment::Left),
+        ],
+    };
+    let test = CliElement::Row {
+        inner: vec![
+            CliElement::print_single(&["##", "##"], Alignment::Left),
+            CliElement::print_constrained(info.clone(), 12, Overflow::Wrap),
+        ],
+        settings: Some(RowSettings {
+            spacing: 1,
+            ..Default::default()
+        }),
+    };
+    assert_eq!(test.width(), 17);
+    assert_eq!(
+        test.render_lines(),
+        vec![
+            " ## \x1b[1;36mCPU\x1b[0m: AMD     ",
+            " ## Ryzen 7      ",
+            "    5800H        ",
+            "    WM: sway     ",
+        ]
+    );
+    let test = CliElement::print_constrained(info, 10, Overflow::Ellipsis);
+    assert_eq!(
+        test.render_lines(),
+        vec!["\x1b[1;36mCPU\x1b[0m: AMD …", "WM: sway  "]
+    );
+}
+
+#[test]
+fn tst_frame() {
+    let cell = |text: &str, style| CliElement::Frame {
+        inner: Box::new(CliElement::print_single(&[text], Alignment::Left)),
+        settings: FrameSettings {
+            style,
+            ..Default::default()
+        },
+    };
+    let grid = CliElement::Column {
+        inner: vec![
+            CliElement::Row {
+                inner: vec![
+                    cell("a", BorderStyle::Single),
+                    cell("bb", BorderStyle::Single),
+                ],
+                settings: None,
+            },
+            cell("ccc", BorderStyle::Single),
+        ],
+    };
+    assert_eq!(
+        grid.render_lines(),
+        vec!["┌─┬──┐", "│a│bb│", "├─┴──┤", "│ccc │", "└────┘"]
+    );
+    let outer = CliElement::Frame {
+        inner: Box::new(grid),
+        settings: FrameSettings {
+            style: BorderStyle::Heavy,
+            title: Some("t".to_string()),
+            padding: 0,
+        },
+    };
+    assert_eq!(outer.width(), 6);
+    assert_eq!(outer.height(), 5);
+    assert_eq!(
+        outer.render_lines(),
+        vec!["┏━ t ┓", "┃a│bb┃", "┣─┴──┫", "┃ccc ┃", "┗━━━━┛"]
+    );
+    let row = CliElement::Row {
+        inner: vec![
+            cell("a", BorderStyle::Ascii),
+            cell("b", BorderStyle::Rounded),
+        ],
+        settings: Some(RowSettings {
+            spacing: 1,
+            ..Default::default()
+        }),
+    };
+    assert_eq!(
+        row.render_lines(),
+        vec![" +-+ ╭─╮ ", " |a| │b│ ", " +-+ ╰─╯ "]
+    );
+}
+
+#[test]
+fn tst_table() {
+    let test = CliElement::Table {
+        rows: vec![
+            ("CPU".to_string(), "AMD Ryzen 7".to_string()),
+            ("Memory".to_string(), "\x1b[32m1 MiB\x1b[0m".to_string()),
+        ],
+        settings: TableSettings {
+            separator: " | ".to_string(),
+            key_alignment: Alignment::Right,
+            ..Default::default()
+        },
+    };
+    assert_eq!(test.width(), 20);
+    assert_eq!(
+        test.render_lines(),
+        vec![
+            "   CPU | AMD Ryzen 7",
+            "Memory | \x1b[32m1 MiB\x1b[0m      "
+        ]
+    );
+    let test = CliElement::Column {
+        inner: vec![
+            CliElement::Table {
+                rows: vec![
+                    ("GPU".to_string(), "AMD\nIntel".to_string()),
+                    ("Disk\nroot".to_string(), "1 GiB".to_string()),
+                ],
+                settings: TableSettings::default(),
+            },
+            CliElement::print_single(&["end"], Alignment::Left),
+        ],
+    };
+    assert_eq!(test.height(), 5);
+    assert_eq!(
+        test.render_lines(),
+        vec![
+            "GPU : AMD  ",
+            "      Intel",
+            "Disk: 1 GiB",
+            "root       ",
+            "end        "
+        ]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn tst_serde() {
+    let layout = r#"{
+        "Row": {
+            "inner": [
+                { "Single": { "inner": ["\u001b[1;36mmarine\u001b[0m"] } },
+                { "Frame": {
+                    "inner": { "Table": { "rows": [["OS", "ArchLinux"]] } },
+                    "settings": { "style": "Rounded", "title": "info" }
+                } }
+            ],
+            "settings": { "spacing": 1, "vertical": "Middle" }
+        }
+    }"#;
+    let element: CliElement = serde_json::from_str(layout).unwrap();
+    assert_eq!(
+        element.render_lines(),
+        vec![
+            "        ╭─ info ──────╮ ",
+            " \x1b[1;36mmarine\x1b[0m │OS: ArchLinux│ ",
+            "        ╰─────────────╯ ",
+        ]
+    );
+    let json = serde_json::to_string(&element).unwrap();
+    let again: CliElement = serde_json::from_str(&json).unwrap();
+    assert_eq!(again.render_lines(), element.render_lines());
+    let layout = r#"{ "Template": {
+        "template": "{os}: {kernel|?:>5}",
+        "data": { "os": "Arch" }
+    } }"#;
+    let element: CliElement = serde_json::from_str(layout).unwrap();
+    assert_eq!(element.render_lines(), vec!["Arch:     ?"]);
+    let json = serde_json::to_string(&element).unwrap();
+    assert!(json.contains(r#""template":"{os}: {kernel|?:>5}""#));
+    assert!(
+        serde_json::from_str::<CliElement>(r#"{ "Template": { "template": "{", "data": {} } }"#)
+            .is_err()
+    );
+}
+
+#[test]
+fn tst_write_to() {
+    struct Broken;
+    impl io::Write for Broken {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken pipe"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let test = CliElement::Column {
+        inner: vec![
+            CliElement::print_single(&["sss"], Alignment::Left),
+            CliElement::print_single(&["b"], Alignment::Center),
+        ],
+    };
+    assert_eq!(test.to_string(), "sss\n b \n");
+    assert!(test.write_to(&mut Broken).is_err());
+}
+
 #[cfg(feature = "nightly")]
 #[test]
 fn tst_len() {
@@ -0,0 +1,110 @@
+//! a bar which shows how full something is
+//! ```
+//! use cliprint::gauge::gauge;
+//! use cliprint::layout::GaugeSettings;
+//! let settings = GaugeSettings {
+//!     width: 10,
+//!     ..Default::default()
+//! };
+//! assert_eq!(gauge(0.4321, &settings).plain_text(), "████▍░░░░░  43%");
+//! ```
+use crate::layout::GaugeSettings;
+use crate::style::{Line, Span, Style};
+
+/// the parts of a cell from one eighth to seven eighths
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// the columns of the label, ` 100%`
+pub(crate) const LABEL_WIDTH: usize = 5;
+
+/// the line of the gauge, the ratio is clamped to `0.0..=1.0`
+pub fn gauge(ratio: f64, settings: &GaugeSettings) -> Line {
+    let ratio = if ratio.is_nan() {
+        0.0
+    } else {
+        ratio.clamp(0.0, 1.0)
+    };
+    let (fill, used) = bar(
+        ratio * settings.width as f64,
+        settings.fill,
+        settings.smooth,
+    );
+    let style = threshold_style(ratio, &settings.thresholds);
+    let empty = settings.width - used;
+    let mut spans = vec![
+        Span::new(fill, style),
+        Span::new(
+            std::iter::repeat_n(settings.empty, empty).collect::<String>(),
+            settings.empty_style,
+        ),
+    ];
+    if settings.label {
+        spans.push(Span::new(
+            format!(" {:>3}%", (ratio * 100.0).round() as usize),
+            Style::new(),
+        ));
+    }
+    spans.retain(|span| !span.text.is_empty());
+    Line::new(spans)
+}
+
+/// the fill of a bar which takes the cells, and the columns of the fill
+///
+/// with smooth and `█`, the last cell is drawn with `▏` to `▉`
+pub(crate) fn bar(cells: f64, fill: char, smooth: bool) -> (String, usize) {
+    let cells = if cells.is_nan() { 0.0 } else { cells.max(0.0) };
+    let (full, partial) = if smooth && fill == '█' {
+        let eighths = (cells * 8.0).round() as usize;
+        match eighths % 8 {
+            0 => (eighths / 8, None),
+            part => (eighths / 8, Some(EIGHTHS[part - 1])),
+        }
+    } else {
+        (cells.round() as usize, None)
+    };
+    let mut output: String = std::iter::repeat_n(fill, full).collect();
+    output.extend(partial);
+    (output, full + usize::from(partial.is_some()))
+}
+
+/// the style of the highest threshold the ratio reaches, the thresholds may be in any order
+pub(crate) fn threshold_style(ratio: f64, thresholds: &[(f64, Style)]) -> Style {
+    thresholds
+        .iter()
+        .filter(|(threshold, _)| ratio >= *threshold)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, style)| *style)
+        .unwrap_or_default()
+}
+
+/// the columns the gauge takes
+pub(crate) fn gauge_width(settings: &GaugeSettings) -> usize {
+    settings.width + if settings.label { LABEL_WIDTH } else { 0 }
+}
+
+#[test]
+fn tst_gauge() {
+    use crate::style::Color;
+    let settings = GaugeSettings {
+        width: 4,
+        label: false,
+        ..Default::default()
+    };
+    assert_eq!(gauge(0.0, &settings).plain_text(), "░░░░");
+    assert_eq!(gauge(1.5, &settings).plain_text(), "████");
+    assert_eq!(gauge(0.53, &settings).plain_text(), "██▏░");
+    assert_eq!(gauge(0.7, &settings).spans[0].style, Color::Yellow.normal());
+    assert_eq!(gauge(0.9, &settings).spans[0].style, Color::Red.normal());
+    let reversed = GaugeSettings {
+        thresholds: settings.thresholds.iter().rev().cloned().collect(),
+        ..settings.clone()
+    };
+    assert_eq!(gauge(0.7, &reversed).spans[0].style, Color::Yellow.normal());
+    let blocky = GaugeSettings {
+        fill: '#',
+        empty: '-',
+        ..settings
+    };
+    assert_eq!(gauge(0.53, &blocky).plain_text(), "##--");
+    assert_eq!(gauge(f64::NAN, &blocky).plain_text(), "----");
+}
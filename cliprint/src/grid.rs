@@ -0,0 +1,264 @@
+//! cells laid out in columns, a cell can span many columns
+//!
+//! the cells are put from left to right, a cell which does not fit in the columns
+//! left in the row goes in the next row. A row is as high as its highest cell
+//! ```
+//! use cliprint::elements::CliElement;
+//! use cliprint::grid::GridCell;
+//! use cliprint::layout::{Alignment, GridColumn, GridSettings};
+//! let cell = |text: &str| GridCell::new(CliElement::print_single(&[text], Alignment::Left));
+//! let a = CliElement::print_grid(
+//!     vec![cell("OS").span(2), cell("cpu"), cell("mem"), cell("gpu")],
+//!     GridSettings {
+//!         columns: vec![GridColumn::Fixed(4), GridColumn::Fraction(1), GridColumn::Fraction(2)],
+//!         column_gap: 1,
+//!         width: Some(14),
+//!         ..Default::default()
+//!     },
+//! );
+//! assert_eq!(a.render_lines(), vec!["OS       cpu  ", "mem  gpu      "]);
+//! ```
+use crate::elements::CliElement;
+use crate::layout::{GridColumn, GridSettings};
+
+/// an element in a grid and the count of columns it takes
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GridCell {
+    pub element: CliElement,
+    /// at least one, and at most the count of columns
+    #[cfg_attr(feature = "serde", serde(default = "one"))]
+    pub span: usize,
+}
+
+#[cfg(feature = "serde")]
+fn one() -> usize {
+    1
+}
+
+impl GridCell {
+    /// a cell in one column
+    pub fn new(element: CliElement) -> Self {
+        Self { element, span: 1 }
+    }
+
+    #[must_use]
+    pub fn span(mut self, span: usize) -> Self {
+        self.span = span;
+        self
+    }
+}
+
+impl From<CliElement> for GridCell {
+    fn from(element: CliElement) -> Self {
+        Self::new(element)
+    }
+}
+
+/// a cell in its row
+#[derive(Clone)]
+pub(crate) struct Placed {
+    /// the index of the cell in the grid
+    pub index: usize,
+    pub column: usize,
+    pub span: usize,
+}
+
+/// the columns of the grid, one auto column when there is none
+pub(crate) fn columns(settings: &GridSettings) -> Vec<GridColumn> {
+    if settings.columns.is_empty() {
+        vec![GridColumn::Auto]
+    } else {
+        settings.columns.clone()
+    }
+}
+
+/// the rows of the cells with the spans
+pub(crate) fn place(spans: &[usize], columns: usize) -> Vec<Vec<Placed>> {
+    let mut rows: Vec<Vec<Placed>> = vec![];
+    let mut column = columns;
+    for (index, span) in spans.iter().enumerate() {
+        let span = (*span).clamp(1, columns);
+        if column + span > columns {
+            rows.push(vec![]);
+            column = 0;
+        }
+        if let Some(row) = rows.last_mut() {
+            row.push(Placed {
+                index,
+                column,
+                span,
+            });
+        }
+        column += span;
+    }
+    rows
+}
+
+/// the widths of the columns, with a total width they fill it without the gaps,
+/// the sizes are the widths of the cells
+pub(crate) fn column_widths(
+    settings: &GridSettings,
+    rows: &[Vec<Placed>],
+    sizes: &[usize],
+    total: Option<usize>,
+) -> Vec<usize> {
+    let columns = columns(settings);
+    let natural = natural_widths(&columns, rows, sizes);
+    let Some(total) = total else {
+        let unit = columns
+            .iter()
+            .zip(&natural)
+            .filter_map(|(column, width)| match column {
+                GridColumn::Fraction(fraction) if *fraction > 0 => Some(width.div_ceil(*fraction)),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        return columns
+            .iter()
+            .zip(natural)
+            .map(|(column, width)| match column {
+                GridColumn::Fixed(fixed) => *fixed,
+                GridColumn::Fraction(fraction) => unit * fraction,
+                GridColumn::Auto | GridColumn::Percent(_) => width,
+            })
+            .collect();
+    };
+    let available = total.saturating_sub(gaps_width(settings, columns.len()));
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .zip(natural)
+        .map(|(column, width)| match column {
+            GridColumn::Fixed(fixed) => *fixed,
+            GridColumn::Percent(percent) => available * percent / 100,
+            GridColumn::Auto => width,
+            GridColumn::Fraction(_) => 0,
+        })
+        .collect();
+    let fractions: usize = columns
+        .iter()
+        .map(|column| match column {
+            GridColumn::Fraction(fraction) => *fraction,
+            _ => 0,
+        })
+        .sum();
+    let rest = available.saturating_sub(widths.iter().sum());
+    let mut left = rest;
+    for (width, column) in widths.iter_mut().zip(&columns) {
+        if let GridColumn::Fraction(fraction) = column {
+            *width = (rest * fraction).checked_div(fractions).unwrap_or(0);
+            left -= *width;
+        }
+    }
+    for (width, column) in widths.iter_mut().zip(&columns) {
+        if left > 0 && matches!(column, GridColumn::Fraction(fraction) if *fraction > 0) {
+            *width += 1;
+            left -= 1;
+        }
+    }
+    widths
+}
+
+/// the widths of the cells in the columns, a cell which spans columns
+/// widens the last of them which is not fixed when they are too narrow for it
+fn natural_widths(columns: &[GridColumn], rows: &[Vec<Placed>], sizes: &[usize]) -> Vec<usize> {
+    let mut widths = vec![0; columns.len()];
+    for placed in rows.iter().flatten().filter(|placed| placed.span == 1) {
+        widths[placed.column] = widths[placed.column].max(sizes[placed.index]);
+    }
+    for placed in rows.iter().flatten().filter(|placed| placed.span > 1) {
+        let spanned = placed.column..placed.column + placed.span;
+        let width: usize = widths[spanned.clone()].iter().sum();
+        if let Some(last) = spanned
+            .rev()
+            .find(|index| !matches!(columns[*index], GridColumn::Fixed(_)))
+        {
+            widths[last] += sizes[placed.index].saturating_sub(width);
+        }
+    }
+    widths
+}
+
+/// the columns of all gaps between the columns
+pub(crate) fn gaps_width(settings: &GridSettings, columns: usize) -> usize {
+    settings.column_gap * columns.saturating_sub(1)
+}
+
+/// the columns a cell takes, with the gaps between its columns
+pub(crate) fn span_width(widths: &[usize], placed: &Placed, gap: usize) -> usize {
+    widths[placed.column..placed.column + placed.span]
+        .iter()
+        .sum::<usize>()
+        + gap * (placed.span - 1)
+}
+
+#[test]
+fn tst_grid() {
+    use crate::layout::Alignment;
+    let cell = |text: &str| GridCell::new(CliElement::print_single(&[text], Alignment::Left));
+    let settings = GridSettings {
+        columns: vec![
+            GridColumn::Auto,
+            GridColumn::Percent(50),
+            GridColumn::Fraction(1),
+            GridColumn::Fraction(2),
+        ],
+        column_gap: 2,
+        ..Default::default()
+    };
+    let cells = [
+        cell("abc"),
+        cell("abcdefgh").span(2),
+        cell("a").span(9),
+        cell("ab"),
+        cell("a"),
+        cell("abc"),
+    ];
+    let spans: Vec<usize> = cells.iter().map(|cell| cell.span).collect();
+    let rows = place(&spans, 4);
+    let sizes: Vec<usize> = cells.iter().map(|cell| cell.element.width()).collect();
+    assert_eq!(
+        rows.iter()
+            .map(|row| row
+                .iter()
+                .map(|placed| (placed.column, placed.span))
+                .collect())
+            .collect::<Vec<Vec<_>>>(),
+        vec![
+            vec![(0, 1), (1, 2)],
+            vec![(0, 4)],
+            vec![(0, 1), (1, 1), (2, 1)]
+        ]
+    );
+    assert_eq!(
+        column_widths(&settings, &rows, &sizes, None),
+        vec![3, 1, 7, 14]
+    );
+    assert_eq!(
+        column_widths(&settings, &rows, &sizes, Some(46)),
+        vec![3, 20, 6, 11]
+    );
+    assert_eq!(
+        column_widths(&settings, &rows, &sizes, Some(10)),
+        vec![3, 2, 0, 0]
+    );
+    let stretched = CliElement::Column {
+        inner: vec![
+            CliElement::print_single(&["0123456789"], Alignment::Left),
+            CliElement::print_grid(
+                vec![cell("a"), cell("b"), cell("cdefgh")],
+                GridSettings {
+                    columns: vec![GridColumn::Fraction(1), GridColumn::Fixed(2)],
+                    column_gap: 1,
+                    stretch: true,
+                    ..Default::default()
+                },
+            ),
+        ],
+    };
+    assert_eq!(
+        stretched.render_lines(),
+        vec!["0123456789", "a       b ", "cdefgh    "]
+    );
+}
@@ -0,0 +1,177 @@
+//! render lines to html, a `<pre>` with a `<span>` for every styled part
+//!
+//! the styles become inline css, so the html can be pasted in a page as it is.
+//! Escape sequences which are no styles, such as pictures, are left out
+//! ```
+//! use cliprint::html::render_html;
+//! use cliprint::layout::HtmlSettings;
+//! use cliprint::style::{Color, Line, Span};
+//! let lines = [Line::from(vec![Color::Cyan.bold().paint("OS"), Span::plain("<Arch>")])];
+//! assert_eq!(
+//!     render_html(&lines, &HtmlSettings::default()),
+//!     "<pre><span style=\"color:#00cdcd;font-weight:bold\">OS</span>&lt;Arch&gt;</pre>\n"
+//! );
+//! ```
+use crate::layout::{HtmlSettings, Theme};
+use crate::style::{Color, Line, Style};
+use crate::text::{Token, tokens};
+
+/// the lines in a `<pre>`, or in a whole page when the settings say so
+pub fn render_html(lines: &[Line], settings: &HtmlSettings) -> String {
+    let theme = settings.theme.as_ref();
+    let mut pre = String::from("<pre");
+    if let Some(theme) = theme {
+        pre.push_str(&format!(
+            " style=\"background-color:{};color:{};font-family:{}\"",
+            hex(theme.background, None),
+            hex(theme.foreground, None),
+            escape(&theme.font_family)
+        ));
+    }
+    pre.push('>');
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            pre.push('\n');
+        }
+        for span in &line.spans {
+            let text: String = tokens(&span.text)
+                .filter_map(|token| match token {
+                    Token::Text(text) => Some(escape(text)),
+                    Token::Escape(_) => None,
+                })
+                .collect();
+            if text.is_empty() {
+                continue;
+            }
+            let css = css(&span.style, theme);
+            if css.is_empty() {
+                pre.push_str(&text);
+            } else {
+                pre.push_str(&format!("<span style=\"{css}\">{text}</span>"));
+            }
+        }
+    }
+    pre.push_str("</pre>\n");
+    if !settings.standalone {
+        return pre;
+    }
+    let mut page = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    if let Some(title) = &settings.title {
+        page.push_str(&format!("<title>{}</title>\n", escape(title)));
+    }
+    page.push_str("</head>\n");
+    match theme {
+        Some(theme) => page.push_str(&format!(
+            "<body style=\"background-color:{}\">\n",
+            hex(theme.background, None)
+        )),
+        None => page.push_str("<body>\n"),
+    }
+    page.push_str(&pre);
+    page.push_str("</body>\n</html>\n");
+    page
+}
+
+/// the inline css of the style
+fn css(style: &Style, theme: Option<&Theme>) -> String {
+    let mut css = vec![];
+    let (fg, bg) = colors(style, theme);
+    if let Some(fg) = fg {
+        css.push(format!("color:{}", hex(fg, theme)));
+    }
+    if let Some(bg) = bg {
+        css.push(format!("background-color:{}", hex(bg, theme)));
+    }
+    for (on, property) in [
+        (style.bold, "font-weight:bold"),
+        (style.dim, "opacity:0.5"),
+        (style.italic, "font-style:italic"),
+        (style.hidden, "visibility:hidden"),
+    ] {
+        if on {
+            css.push(property.to_string());
+        }
+    }
+    match (style.underline, style.strikethrough) {
+        (true, true) => css.push("text-decoration:underline line-through".to_string()),
+        (true, false) => css.push("text-decoration:underline".to_string()),
+        (false, true) => css.push("text-decoration:line-through".to_string()),
+        (false, false) => {}
+    }
+    css.join(";")
+}
+
+/// the foreground and the background of the style, swapped when it is reversed,
+/// then a default color is taken from the theme, or from a light page without theme
+pub(crate) fn colors(style: &Style, theme: Option<&Theme>) -> (Option<Color>, Option<Color>) {
+    if !style.reverse {
+        return (style.fg, style.bg);
+    }
+    let light = Theme::light();
+    let theme = theme.unwrap_or(&light);
+    (
+        Some(style.bg.unwrap_or(theme.background)),
+        Some(style.fg.unwrap_or(theme.foreground)),
+    )
+}
+
+/// the color as `#rrggbb`, the sixteen colors are taken from the theme
+pub(crate) fn hex(color: Color, theme: Option<&Theme>) -> String {
+    let color = match (color, theme) {
+        (Color::Fixed(index @ 0..=15), Some(theme)) => theme.palette[index as usize],
+        (color, Some(theme)) => color
+            .palette_index()
+            .map_or(color, |index| theme.palette[index as usize]),
+        (color, None) => color,
+    };
+    let (r, g, b) = color.to_rgb();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// the text with the characters of markup escaped
+pub(crate) fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            char => output.push(char),
+        }
+    }
+    output
+}
+
+#[test]
+fn tst_render_html() {
+    let lines = [
+        Line::from("\x1b_Gq=2\x1b\\a & b"),
+        Line::from(vec![
+            Color::Red.paint("red"),
+            Style::new().on(Color::Fixed(200)).dim().italic().paint(" "),
+        ]),
+        Line::from_ansi("\x1b[7mx\x1b[0m\x1b[7;31my\x1b[0m"),
+    ];
+    let settings = HtmlSettings {
+        standalone: true,
+        title: Some("marine's fetch".to_string()),
+        theme: Some(Theme::light()),
+    };
+    assert_eq!(
+        render_html(&lines, &settings),
+        [
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+            "<title>marine&#39;s fetch</title>\n</head>\n",
+            "<body style=\"background-color:#ffffff\">\n",
+            "<pre style=\"background-color:#ffffff;color:#000000;font-family:monospace\">",
+            "a &amp; b\n<span style=\"color:#cd0000\">red</span>",
+            "<span style=\"background-color:#ff00d7;opacity:0.5;font-style:italic\"> </span>\n",
+            "<span style=\"color:#ffffff;background-color:#000000\">x</span>",
+            "<span style=\"color:#ffffff;background-color:#cd0000\">y</span>",
+            "</pre>\n</body>\n</html>\n",
+        ]
+        .concat()
+    );
+}
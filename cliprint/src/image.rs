@@ -0,0 +1,553 @@
+//! pictures drawn with the graphics protocols of the terminal, or with characters
+//!
+//! the picture takes a box of cells in the layout. With a graphics protocol the cells
+//! are filled with spaces and the escape sequence which draws the picture is put before
+//! the first cell, half blocks and braille draw the picture in the cells themselves
+use std::fmt::Write;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::layout::ImageProtocol;
+use crate::style::{Color, Line, Span, Style};
+use crate::terminal::ColorSupport;
+
+/// the bytes of base64 in one escape sequence of the kitty protocol
+const KITTY_CHUNK: usize = 4096;
+
+/// the pixels of a cell for sixel, which draws the pixels as they are,
+/// a cell is twice as high as it is wide as [Image::rows_for] takes it
+const SIXEL_CELL_WIDTH: usize = 10;
+const SIXEL_CELL_HEIGHT: usize = 2 * SIXEL_CELL_WIDTH;
+
+/// save the cursor before drawing the picture and restore it after,
+/// so the spaces of the cells are written over the same place
+const SAVE_CURSOR: &str = "\x1b7";
+const RESTORE_CURSOR: &str = "\x1b8";
+
+/// the pixels of a picture, four bytes for each pixel: red, green, blue and alpha
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ImageRepr"))]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+/// an image as it is read, before the count of its pixels is checked
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ImageRepr {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ImageRepr> for Image {
+    type Error = String;
+
+    fn try_from(value: ImageRepr) -> Result<Self, String> {
+        let (width, height, len) = (value.width, value.height, value.pixels.len());
+        Self::from_rgba(width, height, value.pixels).ok_or_else(|| {
+            format!(
+                "a {width}x{height} image needs {} bytes, not {len}",
+                width as usize * height as usize * 4
+            )
+        })
+    }
+}
+
+impl Image {
+    /// none when the pixels are not width * height * 4 bytes
+    pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Option<Self> {
+        if pixels.len() != width as usize * height as usize * 4 {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// read a png file
+    pub fn from_png(bytes: &[u8]) -> Result<Self, png::DecodingError> {
+        let mut decoder = png::Decoder::new(std::io::Cursor::new(bytes));
+        decoder.set_transformations(
+            png::Transformations::normalize_to_color8() | png::Transformations::ALPHA,
+        );
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size().unwrap_or(0)];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 255])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect(),
+            png::ColorType::Grayscale | png::ColorType::Indexed => buffer
+                .iter()
+                .flat_map(|gray| [*gray, *gray, *gray, 255])
+                .collect(),
+        };
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    /// read a jpeg file
+    pub fn from_jpeg(bytes: &[u8]) -> Result<Self, zune_jpeg::errors::DecodeErrors> {
+        let options = zune_jpeg::zune_core::options::DecoderOptions::default()
+            .jpeg_set_out_colorspace(zune_jpeg::zune_core::colorspace::ColorSpace::RGBA);
+        let mut decoder =
+            zune_jpeg::JpegDecoder::new_with_options(std::io::Cursor::new(bytes), options);
+        let pixels = decoder.decode()?;
+        let (width, height) = decoder
+            .dimensions()
+            .expect("the headers are decoded with the pixels");
+        Ok(Self {
+            width: width as u32,
+            height: height as u32,
+            pixels,
+        })
+    }
+
+    /// write the picture as a png file
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut output = vec![];
+        let mut encoder = png::Encoder::new(&mut output, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().expect("write to a Vec never fails");
+        writer
+            .write_image_data(&self.pixels)
+            .expect("the pixels match the size of the picture");
+        writer.finish().expect("write to a Vec never fails");
+        output
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// the rows of cells the picture takes when it takes the columns,
+    /// a cell is twice as high as it is wide
+    pub fn rows_for(&self, columns: usize) -> usize {
+        if self.width == 0 {
+            return 0;
+        }
+        (columns * self.height as usize).div_ceil(2 * self.width as usize)
+    }
+
+    /// the lines of the cells the picture takes
+    /// ```
+    /// use cliprint::image::Image;
+    /// use cliprint::layout::ImageProtocol;
+    /// let image = Image::from_rgba(1, 2, vec![255, 0, 0, 255, 0, 0, 0, 0]).unwrap();
+    /// let lines = image.cells(ImageProtocol::HalfBlock, 1, 1);
+    /// assert_eq!(lines[0].render(), "\x1b[38;2;255;0;0m▀\x1b[0m");
+    /// ```
+    pub fn cells(&self, protocol: ImageProtocol, columns: usize, rows: usize) -> Vec<Line> {
+        match protocol {
+            ImageProtocol::HalfBlock => self.half_blocks(columns, rows),
+            ImageProtocol::Braille => self.braille(columns, rows),
+            protocol => {
+                let mut lines = vec![Line::from(" ".repeat(columns)); rows];
+                if let Some(first) = lines.first_mut() {
+                    first
+                        .spans
+                        .insert(0, Span::plain(self.encode(protocol, columns, rows)));
+                }
+                lines
+            }
+        }
+    }
+
+    /// the escape sequence which draws the picture over the cells,
+    /// the cursor is where it was before
+    /// ```
+    /// use cliprint::image::Image;
+    /// use cliprint::layout::ImageProtocol;
+    /// let image = Image::from_rgba(1, 1, vec![255, 0, 0, 255]).unwrap();
+    /// assert_eq!(
+    ///     image.encode(ImageProtocol::Kitty, 1, 1),
+    ///     "\x1b_Ga=T,f=32,s=1,v=1,c=1,r=1,C=1,q=2;/wAA/w==\x1b\\"
+    /// );
+    /// ```
+    ///
+    /// half blocks and braille have no escape sequence, see [Image::cells]
+    pub fn encode(&self, protocol: ImageProtocol, columns: usize, rows: usize) -> String {
+        match protocol {
+            ImageProtocol::HalfBlock | ImageProtocol::Braille => String::new(),
+            ImageProtocol::Kitty => self.encode_kitty(columns, rows),
+            ImageProtocol::Sixel => format!(
+                "{SAVE_CURSOR}{}{RESTORE_CURSOR}",
+                self.encode_sixel(columns, rows)
+            ),
+            ImageProtocol::Iterm2 => format!(
+                "{SAVE_CURSOR}{}{RESTORE_CURSOR}",
+                self.encode_iterm2(columns, rows)
+            ),
+        }
+    }
+
+    /// the kitty graphics protocol, the picture is sent in chunks,
+    /// `C=1` keeps the cursor where it is
+    fn encode_kitty(&self, columns: usize, rows: usize) -> String {
+        let data = STANDARD.encode(&self.pixels);
+        let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+        let mut output = String::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(index + 1 < chunks.len());
+            let chunk = std::str::from_utf8(chunk).expect("base64 is ascii");
+            if index == 0 {
+                write!(
+                    output,
+                    "\x1b_Ga=T,f=32,s={},v={},c={columns},r={rows},C=1,q=2",
+                    self.width, self.height
+                )
+            } else {
+                write!(output, "\x1b_Gq=2")
+            }
+            .expect("write to a String never fails");
+            if chunks.len() > 1 {
+                write!(output, ",m={more}").expect("write to a String never fails");
+            }
+            write!(output, ";{chunk}\x1b\\").expect("write to a String never fails");
+        }
+        output
+    }
+
+    /// the inline images of iTerm2, the picture is sent as a png file
+    fn encode_iterm2(&self, columns: usize, rows: usize) -> String {
+        let png = self.to_png();
+        format!(
+            "\x1b]1337;File=inline=1;size={};width={columns};height={rows};preserveAspectRatio=0:{}\x07",
+            png.len(),
+            STANDARD.encode(&png)
+        )
+    }
+
+    /// sixel, the colors are the nearest of the 256 colors and a pixel which
+    /// is mostly transparent is not drawn. Sixel draws the pixels as they are,
+    /// so the picture is scaled to the pixels of the cells first
+    fn encode_sixel(&self, columns: usize, rows: usize) -> String {
+        let (width, height) = (columns * SIXEL_CELL_WIDTH, rows * SIXEL_CELL_HEIGHT);
+        let quantized: Vec<Option<u8>> = self
+            .scaled(width, height)
+            .into_iter()
+            .map(|pixel| {
+                if !is_opaque(pixel) {
+                    return None;
+                }
+                match rgb(pixel).downgrade(ColorSupport::Ansi256) {
+                    Some(Color::Fixed(index)) => Some(index),
+                    _ => None,
+                }
+            })
+            .collect();
+        let mut registers: Vec<u8> = vec![];
+        for index in quantized.iter().flatten() {
+            if !registers.contains(index) {
+                registers.push(*index);
+            }
+        }
+        let mut output = format!("\x1bP0;1;0q\"1;1;{width};{height}");
+        for (register, index) in registers.iter().enumerate() {
+            let (r, g, b) = Color::Fixed(*index).to_rgb();
+            let percent = |value: u8| (value as u32 * 100 + 127) / 255;
+            write!(
+                output,
+                "#{register};2;{};{};{}",
+                percent(r),
+                percent(g),
+                percent(b)
+            )
+            .expect("write to a String never fails");
+        }
+        let bands = height.div_ceil(6);
+        for band in 0..bands {
+            let top = band * 6;
+            let bottom = (top + 6).min(height);
+            let mut first = true;
+            for (register, index) in registers.iter().enumerate() {
+                let sixels: Vec<u8> = (0..width)
+                    .map(|x| {
+                        (top..bottom)
+                            .filter(|y| quantized[y * width + x] == Some(*index))
+                            .map(|y| 1 << (y - top))
+                            .sum()
+                    })
+                    .collect();
+                if sixels.iter().all(|sixel| *sixel == 0) {
+                    continue;
+                }
+                if !first {
+                    output.push('$');
+                }
+                first = false;
+                write!(output, "#{register}").expect("write to a String never fails");
+                write_sixels(&mut output, &sixels);
+            }
+            if band + 1 < bands {
+                output.push('-');
+            }
+        }
+        output.push_str("\x1b\\");
+        output
+    }
+
+    /// the pixels of the picture scaled to width * height, a pixel is the average of
+    /// the pixels it covers, their colors weighted by their alpha
+    fn scaled(&self, width: usize, height: usize) -> Vec<[u8; 4]> {
+        let (source_width, source_height) = (self.width as usize, self.height as usize);
+        if source_width == 0 || source_height == 0 {
+            return vec![[0; 4]; width * height];
+        }
+        let mut output = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let rows = covered(y, height, source_height);
+            for x in 0..width {
+                let columns = covered(x, width, source_width);
+                let mut sum = [0u64; 4];
+                let mut count = 0;
+                for source_y in rows.clone() {
+                    for source_x in columns.clone() {
+                        let index = (source_y * source_width + source_x) * 4;
+                        let alpha = self.pixels[index + 3] as u64;
+                        for (sum, value) in sum.iter_mut().zip(&self.pixels[index..index + 3]) {
+                            *sum += *value as u64 * alpha;
+                        }
+                        sum[3] += alpha;
+                        count += 1;
+                    }
+                }
+                output.push(match sum[3] {
+                    0 => [0; 4],
+                    alpha => [
+                        (sum[0] / alpha) as u8,
+                        (sum[1] / alpha) as u8,
+                        (sum[2] / alpha) as u8,
+                        (alpha / count) as u8,
+                    ],
+                });
+            }
+        }
+        output
+    }
+
+    fn half_blocks(&self, columns: usize, rows: usize) -> Vec<Line> {
+        let pixels = self.scaled(columns, rows * 2);
+        (0..rows)
+            .map(|row| {
+                let mut spans = vec![];
+                for column in 0..columns {
+                    let top = pixels[2 * row * columns + column];
+                    let bottom = pixels[(2 * row + 1) * columns + column];
+                    let (char, style) = match (is_opaque(top), is_opaque(bottom)) {
+                        (false, false) => (' ', Style::new()),
+                        (true, false) => ('▀', Style::new().fg(rgb(top))),
+                        (false, true) => ('▄', Style::new().fg(rgb(bottom))),
+                        (true, true) => ('▀', Style::new().fg(rgb(top)).on(rgb(bottom))),
+                    };
+                    push_cell(&mut spans, char, style);
+                }
+                Line::new(spans)
+            })
+            .collect()
+    }
+
+    /// a dot is drawn for every pixel which is mostly opaque,
+    /// the color of the cell is the average of its dots
+    fn braille(&self, columns: usize, rows: usize) -> Vec<Line> {
+        let pixels = self.scaled(columns * 2, rows * 4);
+        (0..rows)
+            .map(|row| {
+                let mut spans = vec![];
+                for column in 0..columns {
+                    let mut dots = 0;
+                    let mut sum = [0u32; 3];
+                    let mut count = 0;
+                    for (dx, dy, dot) in BRAILLE_DOTS {
+                        let pixel = pixels[(row * 4 + dy) * columns * 2 + column * 2 + dx];
+                        if is_opaque(pixel) {
+                            dots |= dot;
+                            for (sum, value) in sum.iter_mut().zip(pixel) {
+                                *sum += value as u32;
+                            }
+                            count += 1;
+                        }
+                    }
+                    if count == 0 {
+                        push_cell(&mut spans, ' ', Style::new());
+                        continue;
+                    }
+                    let color = Color::Rgb(
+                        (sum[0] / count) as u8,
+                        (sum[1] / count) as u8,
+                        (sum[2] / count) as u8,
+                    );
+                    let char = char::from_u32(0x2800 + dots).unwrap_or(' ');
+                    push_cell(&mut spans, char, Style::new().fg(color));
+                }
+                Line::new(spans)
+            })
+            .collect()
+    }
+}
+
+/// the column, the row and the bit of every dot of a braille cell
+const BRAILLE_DOTS: [(usize, usize, u32); 8] = [
+    (0, 0, 0x01),
+    (0, 1, 0x02),
+    (0, 2, 0x04),
+    (1, 0, 0x08),
+    (1, 1, 0x10),
+    (1, 2, 0x20),
+    (0, 3, 0x40),
+    (1, 3, 0x80),
+];
+
+/// the source pixels the index of parts covers, at least one
+fn covered(index: usize, parts: usize, size: usize) -> std::ops::Range<usize> {
+    let start = index * size / parts;
+    let end = ((index + 1) * size / parts).max(start + 1);
+    start..end
+}
+
+fn is_opaque(pixel: [u8; 4]) -> bool {
+    pixel[3] >= 128
+}
+
+fn rgb(pixel: [u8; 4]) -> Color {
+    Color::Rgb(pixel[0], pixel[1], pixel[2])
+}
+
+/// add a cell to the spans, a cell with the style of the last span goes into it
+fn push_cell(spans: &mut Vec<Span>, char: char, style: Style) {
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.text.push(char),
+        _ => spans.push(Span::new(char.to_string(), style)),
+    }
+}
+
+/// the sixels of one color in a band, the empty sixels at the end are left out
+/// and a run of more than three sixels is written with its count
+fn write_sixels(output: &mut String, sixels: &[u8]) {
+    let end = sixels
+        .iter()
+        .rposition(|sixel| *sixel != 0)
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+    let mut index = 0;
+    while index < end {
+        let sixel = sixels[index];
+        let run = sixels[index..end]
+            .iter()
+            .take_while(|next| **next == sixel)
+            .count();
+        let char = (63 + sixel) as char;
+        if run > 3 {
+            write!(output, "!{run}{char}").expect("write to a String never fails");
+        } else {
+            output.extend(std::iter::repeat_n(char, run));
+        }
+        index += run;
+    }
+}
+
+#[test]
+fn tst_image_encode() {
+    let red = [255, 0, 0, 255];
+    let blue = [0, 0, 255, 255];
+    let clear = [0, 0, 0, 0];
+    let pixels: Vec<u8> = [
+        red, red, red, red, red, blue, //
+        red, clear, clear, clear, clear, blue, //
+    ]
+    .concat();
+    let image = Image::from_rgba(6, 2, pixels).unwrap();
+    assert_eq!(image.rows_for(6), 1);
+    assert_eq!(
+        image.encode(ImageProtocol::Sixel, 6, 1),
+        include_str!("../assert/image.sixel").trim_end()
+    );
+    assert_eq!(
+        image.encode(ImageProtocol::Kitty, 6, 1),
+        include_str!("../assert/image.kitty").trim_end()
+    );
+    assert_eq!(
+        image.encode(ImageProtocol::Iterm2, 6, 1),
+        include_str!("../assert/image.iterm2").trim_end()
+    );
+    assert_eq!(Image::from_png(&image.to_png()).unwrap(), image);
+    let large = Image::from_rgba(64, 32, vec![7; 64 * 32 * 4]).unwrap();
+    let kitty = large.encode(ImageProtocol::Kitty, 8, 2);
+    assert_eq!(kitty.matches("\x1b_G").count(), 3);
+    assert_eq!(kitty.matches(",m=1;").count(), 2);
+    assert!(kitty.ends_with("Bwc=\x1b\\"));
+    let sixel = Image::from_rgba(64, 32, [255, 0, 0, 255].repeat(64 * 32))
+        .unwrap()
+        .encode(ImageProtocol::Sixel, 8, 2);
+    assert!(sixel.contains("\"1;1;80;40#"));
+    assert_eq!(sixel.matches('-').count(), 6);
+}
+
+#[test]
+fn tst_image_cells() {
+    let white = [255, 255, 255, 255];
+    let black = [0, 0, 0, 255];
+    let clear = [0, 0, 0, 0];
+    let pixels: Vec<u8> = [
+        white, white, clear, clear, //
+        black, clear, clear, white, //
+        black, black, black, black, //
+        clear, clear, clear, clear, //
+    ]
+    .concat();
+    let image = Image::from_rgba(4, 4, pixels).unwrap();
+    let white = Color::Rgb(255, 255, 255);
+    let black = Color::Rgb(0, 0, 0);
+    assert_eq!(
+        image.cells(ImageProtocol::HalfBlock, 4, 2)[0].spans,
+        vec![
+            Style::new().fg(white).on(black).paint("▀"),
+            Style::new().fg(white).paint("▀"),
+            Style::new().paint(" "),
+            Style::new().fg(white).paint("▄"),
+        ]
+    );
+    assert_eq!(
+        image.cells(ImageProtocol::HalfBlock, 4, 2)[1].plain_text(),
+        "▀▀▀▀"
+    );
+    let braille = image.cells(ImageProtocol::Braille, 2, 1);
+    assert_eq!(braille[0].plain_text(), "⠯⠴");
+    assert_eq!(
+        braille[0].spans[1].style,
+        Style::new().fg(Color::Rgb(85, 85, 85))
+    );
+    let scaled = image.cells(ImageProtocol::HalfBlock, 2, 1);
+    assert_eq!(scaled[0].plain_text(), "▀ ");
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&image).unwrap();
+        assert_eq!(serde_json::from_str::<Image>(&json).unwrap(), image);
+        let short = r#"{"width":4,"height":4,"pixels":[1,2]}"#;
+        let error = serde_json::from_str::<Image>(short).unwrap_err();
+        assert_eq!(error.to_string(), "a 4x4 image needs 64 bytes, not 2");
+    }
+}
@@ -1,11 +1,396 @@
-#[derive(Clone, Copy, Debug, Default)]
+use crate::style::{Color, Style};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Alignment {
     #[default]
     Left,
     Right,
+    Center,
+}
+
+/// where a child shorter than the row is placed
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VerticalAlignment {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct RowSettings {
     pub spacing: usize,
+    pub vertical: VerticalAlignment,
+}
+
+/// what to do with a line wider than the max width
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Overflow {
+    /// cut the line and end it with `…`
+    #[default]
+    Ellipsis,
+    /// cut the line
+    Clip,
+    /// break the line at spaces, or anywhere when a word is too long
+    Wrap,
+}
+
+/// the glyphs a frame is drawn with
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BorderStyle {
+    #[default]
+    Single,
+    Double,
+    Rounded,
+    Heavy,
+    Ascii,
+}
+
+/// the graphics protocol a picture is drawn with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageProtocol {
+    #[default]
+    Kitty,
+    Sixel,
+    Iterm2,
+    /// `▀` and `▄` with colors, a cell shows two pixels, one above the other
+    HalfBlock,
+    /// braille dots with colors, a cell shows two by four pixels
+    Braille,
+}
+
+/// how a frame is drawn around the inner element
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct FrameSettings {
+    pub style: BorderStyle,
+    /// shown in the top edge
+    pub title: Option<String>,
+    /// spaces between the border and the inner element, on the left and the right
+    pub padding: usize,
+}
+
+/// how the keys, the separators and the values of a table are lined up
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct TableSettings {
+    /// put between every key and value
+    pub separator: String,
+    /// how the keys are aligned in their column
+    pub key_alignment: Alignment,
+    pub key_style: Style,
+    pub separator_style: Style,
+    pub value_style: Style,
+}
+
+impl Default for TableSettings {
+    fn default() -> Self {
+        Self {
+            separator: ": ".to_string(),
+            key_alignment: Alignment::Left,
+            key_style: Style::default(),
+            separator_style: Style::default(),
+            value_style: Style::default(),
+        }
+    }
+}
+
+/// how a gauge is drawn
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct GaugeSettings {
+    /// the columns of the bar, the label is not in them
+    pub width: usize,
+    pub fill: char,
+    pub empty: char,
+    /// the last cell of the fill is drawn with `▏` to `▉`, so the bar moves by an eighth
+    /// of a cell, only when the fill is `█`
+    pub smooth: bool,
+    /// the fill takes the style of the highest threshold the ratio reaches
+    pub thresholds: Vec<(f64, Style)>,
+    pub empty_style: Style,
+    /// show the percentage after the bar
+    pub label: bool,
+}
+
+impl Default for GaugeSettings {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            fill: '█',
+            empty: '░',
+            smooth: true,
+            thresholds: vec![
+                (0.0, Color::Green.normal()),
+                (0.6, Color::Yellow.normal()),
+                (0.85, Color::Red.normal()),
+            ],
+            empty_style: Style::new().dim(),
+            label: true,
+        }
+    }
+}
+
+/// how a sparkline scales the values
+#[derive(Clone, Copy, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SparklineSettings {
+    /// the value of the lowest bar, the smallest value when none
+    pub min: Option<f64>,
+    /// the value of the highest bar, the largest value when none
+    pub max: Option<f64>,
+    pub style: Style,
+}
+
+/// how a bar chart is drawn
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct BarChartSettings {
+    /// the columns of a full bar
+    pub width: usize,
+    /// the value of a full bar, the largest value when none
+    pub max: Option<f64>,
+    pub fill: char,
+    /// the same as [GaugeSettings::smooth]
+    pub smooth: bool,
+    /// a bar takes the style of the highest threshold its ratio to max reaches
+    pub thresholds: Vec<(f64, Style)>,
+    pub label_style: Style,
+    /// show the value after the bar
+    pub values: bool,
+    /// a line between the labels and the bars, and a scale under the bars
+    pub axis: bool,
+}
+
+impl Default for BarChartSettings {
+    fn default() -> Self {
+        Self {
+            width: 20,
+            max: None,
+            fill: '█',
+            smooth: true,
+            thresholds: vec![],
+            label_style: Style::default(),
+            values: true,
+            axis: false,
+        }
+    }
+}
+
+/// the width of a column of a grid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GridColumn {
+    /// as wide as the widest cell in it
+    #[default]
+    Auto,
+    /// columns
+    Fixed(usize),
+    /// a part of the width of the grid without the gaps,
+    /// as [GridColumn::Auto] when the width is unknown
+    Percent(usize),
+    /// a share of the width which the other columns leave,
+    /// a column with `Fraction(2)` is twice as wide as one with `Fraction(1)`
+    Fraction(usize),
+}
+
+/// how the columns of a grid are laid out
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct GridSettings {
+    /// a grid without columns has one auto column
+    pub columns: Vec<GridColumn>,
+    /// spaces between the columns
+    pub column_gap: usize,
+    /// empty lines between the rows
+    pub row_gap: usize,
+    /// the width of the whole grid, such as [terminal_width](crate::terminal::terminal_width),
+    /// the natural width of the columns when none
+    pub width: Option<usize>,
+    /// the fraction columns take the whole width the grid is drawn with,
+    /// when it is wider than the grid, in a column with a wider element
+    pub stretch: bool,
+}
+
+/// the way the colors of a gradient go
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GradientDirection {
+    /// from the left to the right
+    #[default]
+    Horizontal,
+    /// from the top to the bottom
+    Vertical,
+    /// from the top left to the bottom right
+    Diagonal,
+}
+
+/// how the characters of an element are colored, the spaces keep their style
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Colorize {
+    /// the colors go from stop to stop, evenly spaced
+    Gradient {
+        stops: Vec<Color>,
+        #[cfg_attr(feature = "serde", serde(default))]
+        direction: GradientDirection,
+    },
+    /// the rainbow of lolcat, the hue turns with the frequency every spread columns
+    /// and every line
+    Rainbow {
+        frequency: f64,
+        spread: f64,
+        /// where the rainbow starts
+        offset: f64,
+    },
+    /// the color of every character, the other characters keep their style
+    CharMap(Vec<(char, Color)>),
+}
+
+impl Colorize {
+    /// the rainbow as lolcat draws it by default
+    pub fn rainbow() -> Self {
+        Colorize::Rainbow {
+            frequency: 0.1,
+            spread: 3.0,
+            offset: 0.0,
+        }
+    }
+}
+
+/// the colors an element is rendered with to html or svg
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Theme {
+    pub background: Color,
+    pub foreground: Color,
+    /// the sixteen colors of the palette, the other colors are shown as they are
+    pub palette: [Color; 16],
+    pub font_family: String,
+}
+
+impl Theme {
+    /// light text on black, with the palette of xterm
+    pub fn dark() -> Self {
+        Self {
+            background: Color::Rgb(0, 0, 0),
+            foreground: Color::Rgb(229, 229, 229),
+            palette: std::array::from_fn(|index| {
+                let (r, g, b) = Color::Fixed(index as u8).to_rgb();
+                Color::Rgb(r, g, b)
+            }),
+            font_family: "monospace".to_string(),
+        }
+    }
+
+    /// dark text on white, with the palette of xterm a bit darker
+    pub fn light() -> Self {
+        let mut palette = Self::dark().palette;
+        palette[3] = Color::Rgb(160, 140, 0);
+        palette[7] = Color::Rgb(120, 120, 120);
+        palette[11] = Color::Rgb(190, 170, 0);
+        palette[15] = Color::Rgb(60, 60, 60);
+        Self {
+            background: Color::Rgb(255, 255, 255),
+            foreground: Color::Rgb(0, 0, 0),
+            palette,
+            ..Self::dark()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// how an element is rendered to html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct HtmlSettings {
+    /// a whole page with `<html>` around the `<pre>`, only the `<pre>` when false
+    pub standalone: bool,
+    /// the title of the whole page
+    pub title: Option<String>,
+    /// the colors of the `<pre>` and of the palette as inline css,
+    /// the page decides the colors of the `<pre>` when none
+    pub theme: Option<Theme>,
+}
+
+/// how an element is drawn to svg, as a screenshot of a terminal
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SvgSettings {
+    pub theme: Theme,
+    /// in pixels, as the sizes below
+    pub font_size: usize,
+    pub cell_width: usize,
+    pub cell_height: usize,
+    /// between the edge of the window and the text
+    pub padding: usize,
+    /// a title bar with the three buttons of a window, and rounded corners
+    pub chrome: bool,
+    /// shown in the title bar
+    pub title: Option<String>,
+}
+
+impl Default for SvgSettings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            font_size: 14,
+            cell_width: 9,
+            cell_height: 18,
+            padding: 10,
+            chrome: true,
+            title: None,
+        }
+    }
 }
@@ -1,3 +1,23 @@
-#![cfg_attr(feature = "nightly", feature(coroutines, coroutine_trait))]
+#![cfg_attr(
+    feature = "nightly",
+    feature(coroutines, coroutine_trait, async_iterator)
+)]
+mod border;
+pub mod chart;
+pub mod colorize;
 pub mod elements;
+pub mod gauge;
+pub mod grid;
+pub mod html;
+pub mod image;
 pub mod layout;
+pub mod live;
+mod macros;
+mod measure;
+pub mod overlay;
+pub mod producer;
+pub mod style;
+pub mod svg;
+pub mod template;
+pub mod terminal;
+pub mod text;
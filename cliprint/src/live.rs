@@ -0,0 +1,233 @@
+//! draw an element again and again at the same place, as `watch` does
+//!
+//! the cursor goes back up to the first line of the last frame, and only the lines
+//! which changed are written again. A frame is cut to the size of the terminal, as the
+//! cursor can not go back up to the lines which scrolled out of the screen. When the
+//! terminal is resized the screen is cleared, because the terminal may have wrapped the
+//! lines of the last frame
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use signal_hook::consts::SIGWINCH;
+use signal_hook::consts::{SIGINT, SIGTERM};
+
+use crate::elements::CliElement;
+use crate::layout::Overflow;
+use crate::terminal::{ColorSupport, terminal_height, terminal_width};
+
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const CLEAR_LINE: &str = "\x1b[2K";
+const CLEAR_BELOW: &str = "\x1b[J";
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// how long watch sleeps before it looks for Ctrl-C or a resize again
+const TICK: Duration = Duration::from_millis(50);
+
+/// draws frames over each other, the cursor is hidden while it draws
+/// and shown again when it is finished or dropped
+/// ```
+/// use cliprint::elements::CliElement;
+/// use cliprint::layout::Alignment;
+/// use cliprint::live::LiveRenderer;
+/// use cliprint::terminal::ColorSupport;
+/// let mut renderer = LiveRenderer::new(vec![], ColorSupport::None);
+/// let frame = |uptime: &str| CliElement::print_single(&["marine", uptime], Alignment::Left);
+/// renderer.render_with_size(&frame("1 min"), None, None).unwrap();
+/// renderer.render_with_size(&frame("2 min"), None, None).unwrap();
+/// let output = String::from_utf8(renderer.finish().unwrap()).unwrap();
+/// assert_eq!(
+///     output,
+///     "\x1b[?25l\x1b[2Kmarine\n\x1b[2K1 min \n\x1b[2A\r\n\x1b[2K2 min \n\x1b[?25h"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct LiveRenderer<W: io::Write> {
+    writer: Option<W>,
+    support: ColorSupport,
+    /// the lines of the last frame
+    previous: Vec<String>,
+    /// the width and the height of the terminal when the last frame was drawn
+    size: (Option<usize>, Option<usize>),
+    cursor_hidden: bool,
+}
+
+impl LiveRenderer<io::Stdout> {
+    /// draw to stdout with the colors it can show
+    pub fn stdout() -> Self {
+        Self::new(io::stdout(), ColorSupport::detect())
+    }
+}
+
+impl<W: io::Write> LiveRenderer<W> {
+    pub fn new(writer: W, support: ColorSupport) -> Self {
+        Self {
+            writer: Some(writer),
+            support,
+            previous: vec![],
+            size: (None, None),
+            cursor_hidden: false,
+        }
+    }
+
+    /// draw the element over the last frame, it is cut to the size of the terminal
+    pub fn render(&mut self, element: &CliElement) -> io::Result<()> {
+        self.render_with_size(element, terminal_width(), terminal_height())
+    }
+
+    /// the same as [LiveRenderer::render], with the size of the terminal given,
+    /// the element is not cut when the width or the height is none. A line is kept
+    /// free below the frame for the cursor, so the frame takes at most height - 1 lines
+    pub fn render_with_size(
+        &mut self,
+        element: &CliElement,
+        width: Option<usize>,
+        height: Option<usize>,
+    ) -> io::Result<()> {
+        let fit;
+        let element = match width {
+            Some(width) => {
+                fit = CliElement::print_constrained(element.clone(), width, Overflow::Ellipsis);
+                &fit
+            }
+            None => element,
+        };
+        let lines: Vec<String> = element
+            .lines_with(self.support)
+            .take(height.map_or(usize::MAX, |height| height.saturating_sub(1)))
+            .collect();
+        let mut output = String::new();
+        if !self.cursor_hidden {
+            output.push_str(HIDE_CURSOR);
+            self.cursor_hidden = true;
+        }
+        if !self.previous.is_empty() && (width, height) != self.size {
+            output.push_str(CLEAR_SCREEN);
+            self.previous.clear();
+        }
+        if !self.previous.is_empty() {
+            output.push_str(&format!("\x1b[{}A\r", self.previous.len()));
+        }
+        for (index, line) in lines.iter().enumerate() {
+            if self.previous.get(index) != Some(line) {
+                output.push_str(CLEAR_LINE);
+                output.push_str(line);
+            }
+            output.push('\n');
+        }
+        if lines.len() < self.previous.len() {
+            output.push_str(CLEAR_BELOW);
+        }
+        let writer = self
+            .writer
+            .as_mut()
+            .expect("the writer is kept until finish");
+        writer.write_all(output.as_bytes())?;
+        writer.flush()?;
+        self.previous = lines;
+        self.size = (width, height);
+        Ok(())
+    }
+
+    /// show the cursor again and give back the writer, the last frame stays
+    pub fn finish(mut self) -> io::Result<W> {
+        self.restore()?;
+        Ok(self.writer.take().expect("the writer is kept until finish"))
+    }
+
+    fn restore(&mut self) -> io::Result<()> {
+        if let Some(writer) = self.writer.as_mut()
+            && self.cursor_hidden
+        {
+            self.cursor_hidden = false;
+            writer.write_all(SHOW_CURSOR.as_bytes())?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: io::Write> Drop for LiveRenderer<W> {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+/// draw the element the closure builds every interval on stdout, until Ctrl-C or
+/// SIGTERM, then the cursor is shown again. The element is drawn again when the
+/// terminal is resized
+pub fn watch(interval: Duration, mut build: impl FnMut() -> CliElement) -> io::Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let resized = Arc::new(AtomicBool::new(false));
+    let ids = [
+        signal_hook::flag::register(SIGINT, Arc::clone(&stop))?,
+        signal_hook::flag::register(SIGTERM, Arc::clone(&stop))?,
+        #[cfg(unix)]
+        signal_hook::flag::register(SIGWINCH, Arc::clone(&resized))?,
+    ];
+    let mut renderer = LiveRenderer::stdout();
+    let mut result = Ok(());
+    'frames: while !stop.load(Ordering::Relaxed) {
+        let element = build();
+        let start = Instant::now();
+        loop {
+            if let Err(error) = renderer.render(&element) {
+                result = Err(error);
+                break 'frames;
+            }
+            while !stop.load(Ordering::Relaxed)
+                && start.elapsed() < interval
+                && !resized.swap(false, Ordering::Relaxed)
+            {
+                std::thread::sleep(TICK.min(interval.saturating_sub(start.elapsed())));
+            }
+            if stop.load(Ordering::Relaxed) || start.elapsed() >= interval {
+                break;
+            }
+        }
+    }
+    for id in ids {
+        signal_hook::low_level::unregister(id);
+    }
+    renderer.finish()?;
+    result
+}
+
+#[test]
+fn tst_live_renderer() {
+    use crate::layout::Alignment;
+    let mut renderer = LiveRenderer::new(vec![], ColorSupport::None);
+    let frame = |lines: &[&str]| CliElement::print_single(lines, Alignment::Left);
+    renderer
+        .render_with_size(&frame(&["a", "b", "c"]), Some(10), None)
+        .unwrap();
+    renderer
+        .render_with_size(&frame(&["a"]), Some(10), None)
+        .unwrap();
+    renderer
+        .render_with_size(&frame(&["abc"]), Some(2), None)
+        .unwrap();
+    // taller than the terminal, the lines which do not fit are left out
+    renderer
+        .render_with_size(&frame(&["1", "2", "3", "4"]), Some(2), Some(3))
+        .unwrap();
+    renderer
+        .render_with_size(&frame(&["1", "5", "6", "7"]), Some(2), Some(3))
+        .unwrap();
+    let output = String::from_utf8(renderer.finish().unwrap()).unwrap();
+    assert_eq!(
+        output,
+        [
+            "\x1b[?25l\x1b[2Ka\n\x1b[2Kb\n\x1b[2Kc\n",
+            "\x1b[3A\r\n\x1b[J",
+            "\x1b[H\x1b[2J\x1b[2Ka…\n",
+            "\x1b[H\x1b[2J\x1b[2K1\n\x1b[2K2\n",
+            "\x1b[2A\r\n\x1b[2K5\n",
+            "\x1b[?25h",
+        ]
+        .concat()
+    );
+}
@@ -0,0 +1,287 @@
+//! build a tree of [CliElement](crate::elements::CliElement) with one syntax,
+//! the tree is the same with the `nightly` feature or without it
+
+/// declare a tree of elements
+///
+/// * `row(spacing = 1, vertical = VerticalAlignment::Middle) { .. }`, the settings are
+///   the fields of [RowSettings](crate::layout::RowSettings) and can be left out
+/// * `column { .. }`
+/// * `single(text)` or `single(text, Alignment::Right)`, the lines of the text are the lines of the element
+/// * `empty`
+/// * `gauge(0.4)` or `gauge(0.4, width = 10, label = false)`, the settings are the fields of
+///   [GaugeSettings](crate::layout::GaugeSettings)
+/// * `frame(title = Some("info".to_string())) { child }`, the settings are the fields of
+///   [FrameSettings](crate::layout::FrameSettings)
+/// * `sparkline(values)` or `sparkline(values, max = Some(100.0))`, the values are any
+///   iterator of f64
+/// * `bar_chart(width = 10) { "cpu0" => 12.5, "cpu1" => 40 }`, the settings are the fields of
+///   [BarChartSettings](crate::layout::BarChartSettings)
+/// * `grid(columns = vec![GridColumn::Fraction(1); 2]) { .. }`, the settings are the fields of
+///   [GridSettings](crate::layout::GridSettings), every child takes one column
+/// * `table(separator = " -> ".to_string()) { "OS" => os, "Kernel" => kernel }`
+/// * `constrained(40, Overflow::Wrap) { child }`
+/// * `colorized(Colorize::rainbow()) { child }`
+/// * `element(expr)` puts an element built somewhere else in the tree
+/// * `..iter` in a row or a column puts every element of the iterator there
+///
+/// ```
+/// use cliprint::cli;
+/// use cliprint::layout::Alignment;
+/// let gpus = vec!["AMD", "Intel"];
+/// let a = cli! {
+///     row(spacing = 1) {
+///         single("##\n##"),
+///         column {
+///             single("marine"),
+///             single("---", Alignment::Right),
+///             ..gpus.iter().map(|gpu| cli!(single(format!("GPU: {}", gpu)))),
+///         },
+///     }
+/// };
+/// assert_eq!(
+///     a.render_lines(),
+///     vec![
+///         " ## marine     ",
+///         " ##        --- ",
+///         "    GPU: AMD   ",
+///         "    GPU: Intel ",
+///     ]
+/// );
+/// ```
+#[macro_export]
+macro_rules! cli {
+    (row $(($($key:ident = $value:expr),* $(,)?))? { $($body:tt)* }) => {
+        $crate::elements::CliElement::Row {
+            inner: $crate::cli!(@children $($body)*),
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::RowSettings {
+                    $($($key: $value,)*)?
+                    ..::core::default::Default::default()
+                };
+                Some(settings)
+            },
+        }
+    };
+    (column { $($body:tt)* }) => {
+        $crate::elements::CliElement::Column {
+            inner: $crate::cli!(@children $($body)*),
+        }
+    };
+    (single($text:expr $(,)?)) => {
+        $crate::elements::CliElement::print_single_from_str(
+            &$text,
+            $crate::layout::Alignment::Left,
+        )
+    };
+    (single($text:expr, $layout:expr $(,)?)) => {
+        $crate::elements::CliElement::print_single_from_str(&$text, $layout)
+    };
+    (empty) => {
+        $crate::elements::CliElement::EmptyBlock
+    };
+    (gauge($ratio:expr $(, $key:ident = $value:expr)* $(,)?)) => {
+        $crate::elements::CliElement::Gauge {
+            ratio: $ratio,
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::GaugeSettings {
+                    $($key: $value,)*
+                    ..::core::default::Default::default()
+                };
+                settings
+            },
+        }
+    };
+    (frame $(($($key:ident = $value:expr),* $(,)?))? { $($body:tt)* }) => {
+        $crate::elements::CliElement::Frame {
+            inner: ::std::boxed::Box::new($crate::cli!($($body)*)),
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::FrameSettings {
+                    $($($key: $value,)*)?
+                    ..::core::default::Default::default()
+                };
+                settings
+            },
+        }
+    };
+    (table $(($($key:ident = $value:expr),* $(,)?))? { $($name:expr => $row:expr),* $(,)? }) => {
+        $crate::elements::CliElement::Table {
+            rows: vec![$(($name.to_string(), $row.to_string())),*],
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::TableSettings {
+                    $($($key: $value,)*)?
+                    ..::core::default::Default::default()
+                };
+                settings
+            },
+        }
+    };
+    (sparkline($values:expr $(, $key:ident = $value:expr)* $(,)?)) => {
+        $crate::elements::CliElement::Sparkline {
+            values: ::std::iter::Iterator::collect(::std::iter::IntoIterator::into_iter($values)),
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::SparklineSettings {
+                    $($key: $value,)*
+                    ..::core::default::Default::default()
+                };
+                settings
+            },
+        }
+    };
+    (bar_chart $(($($key:ident = $value:expr),* $(,)?))? { $($name:expr => $bar:expr),* $(,)? }) => {
+        $crate::elements::CliElement::BarChart {
+            bars: vec![$(($name.to_string(), $bar as f64)),*],
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::BarChartSettings {
+                    $($($key: $value,)*)?
+                    ..::core::default::Default::default()
+                };
+                settings
+            },
+        }
+    };
+    (grid $(($($key:ident = $value:expr),* $(,)?))? { $($body:tt)* }) => {
+        $crate::elements::CliElement::Grid {
+            cells: {
+                #[allow(clippy::vec_init_then_push)]
+                let inner = $crate::cli!(@children $($body)*);
+                ::std::iter::Iterator::collect(::std::iter::Iterator::map(
+                    ::std::iter::IntoIterator::into_iter(inner),
+                    $crate::grid::GridCell::new,
+                ))
+            },
+            settings: {
+                #[allow(clippy::needless_update)]
+                let settings = $crate::layout::GridSettings {
+                    $($($key: $value,)*)?
+                    ..::core::default::Default::default()
+                };
+                settings
+            },
+        }
+    };
+    (constrained($max_width:expr, $overflow:expr $(,)?) { $($body:tt)* }) => {
+        $crate::elements::CliElement::print_constrained(
+            $crate::cli!($($body)*),
+            $max_width,
+            $overflow,
+        )
+    };
+    (colorized($colorize:expr $(,)?) { $($body:tt)* }) => {
+        $crate::elements::CliElement::print_colorized($crate::cli!($($body)*), $colorize)
+    };
+    (element($element:expr $(,)?)) => {
+        $element
+    };
+    (@children $($body:tt)*) => {{
+        #[allow(unused_mut, clippy::vec_init_then_push)]
+        let mut inner: ::std::vec::Vec<$crate::elements::CliElement> = ::std::vec::Vec::new();
+        $crate::cli!(@push inner $($body)*);
+        inner
+    }};
+    (@push $inner:ident) => {};
+    (@push $inner:ident ..$iter:expr $(, $($rest:tt)*)?) => {
+        $inner.extend($iter);
+        $($crate::cli!(@push $inner $($rest)*);)?
+    };
+    (@push $inner:ident $name:ident $(($($args:tt)*))? $({ $($body:tt)* })? $(, $($rest:tt)*)?) => {
+        $inner.push($crate::cli!($name $(($($args)*))? $({ $($body)* })?));
+        $($crate::cli!(@push $inner $($rest)*);)?
+    };
+}
+
+#[test]
+fn tst_cli_macro() {
+    use crate::elements::CliElement;
+    use crate::layout::{
+        Alignment, BarChartSettings, Colorize, FrameSettings, GaugeSettings, GridColumn,
+        GridSettings, Overflow, RowSettings, VerticalAlignment,
+    };
+    use crate::style::Color;
+    let os = "ArchLinux".to_string();
+    let tree = cli! {
+        column {
+            row(spacing = 1, vertical = VerticalAlignment::Bottom) {
+                frame(title = Some("a".to_string())) { single("x") },
+                table { "OS" => os, "Up" => 3 },
+            },
+            colorized(Colorize::CharMap(vec![('c', Color::Red)])) {
+                constrained(4, Overflow::Clip) { single("abcdef") }
+            },
+            grid(columns = vec![GridColumn::Fixed(2); 2]) { single("g"), empty, single("h") },
+            empty,
+            gauge(0.5, width = 4, label = false),
+            bar_chart(width = 4) { "a" => 1, "b" => 2.5 },
+            element(CliElement::print_single(&["e"], Alignment::Left)),
+        }
+    };
+    let manual = CliElement::Column {
+        inner: vec![
+            CliElement::Row {
+                inner: vec![
+                    CliElement::print_frame(
+                        CliElement::print_single(&["x"], Alignment::Left),
+                        FrameSettings {
+                            title: Some("a".to_string()),
+                            ..Default::default()
+                        },
+                    ),
+                    CliElement::Table {
+                        rows: vec![
+                            ("OS".to_string(), "ArchLinux".to_string()),
+                            ("Up".to_string(), "3".to_string()),
+                        ],
+                        settings: Default::default(),
+                    },
+                ],
+                settings: Some(RowSettings {
+                    spacing: 1,
+                    vertical: VerticalAlignment::Bottom,
+                }),
+            },
+            CliElement::print_colorized(
+                CliElement::print_constrained(
+                    CliElement::print_single(&["abcdef"], Alignment::Left),
+                    4,
+                    Overflow::Clip,
+                ),
+                Colorize::CharMap(vec![('c', Color::Red)]),
+            ),
+            CliElement::print_grid(
+                vec![
+                    CliElement::print_single(&["g"], Alignment::Left).into(),
+                    CliElement::EmptyBlock.into(),
+                    CliElement::print_single(&["h"], Alignment::Left).into(),
+                ],
+                GridSettings {
+                    columns: vec![GridColumn::Fixed(2); 2],
+                    ..Default::default()
+                },
+            ),
+            CliElement::EmptyBlock,
+            CliElement::print_gauge(
+                0.5,
+                GaugeSettings {
+                    width: 4,
+                    label: false,
+                    ..Default::default()
+                },
+            ),
+            CliElement::print_bar_chart(
+                vec![("a".to_string(), 1.0), ("b".to_string(), 2.5)],
+                BarChartSettings {
+                    width: 4,
+                    ..Default::default()
+                },
+            ),
+            CliElement::print_single(&["e"], Alignment::Left),
+        ],
+    };
+    assert_eq!(tree.render_lines(), manual.render_lines());
+    assert_eq!(tree.height(), 11);
+}
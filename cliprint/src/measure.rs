@@ -0,0 +1,729 @@
+//! the layout of a tree in two passes, every element is measured once from the
+//! leaves to the root, then the lines are drawn with the sizes which were measured
+//!
+//! the lines are drawn when they are taken, a column draws its children one after the
+//! other and a row draws a line of every child for each of its lines, so only a row of
+//! a grid is kept at once
+use std::iter;
+
+use crate::border::{
+    bottom_edge, merge_edge, merge_line, merge_line_over, merge_over, title_width, top_edge,
+};
+use crate::chart::{bar_chart, sparkline};
+use crate::colorize::colorize_line;
+use crate::elements::CliElement;
+use crate::gauge::{gauge, gauge_width};
+use crate::grid::{Placed, column_widths, columns, gaps_width, place, span_width};
+use crate::layout::{Alignment, FrameSettings, GridSettings, Overflow, VerticalAlignment};
+use crate::overlay::{cells, put, render};
+use crate::style::{Line, Span};
+use crate::text::{display_width, pad, truncate, wrap};
+
+/// the lines of an element, drawn when they are taken
+pub(crate) type Lines<'a> = Box<dyn Iterator<Item = String> + 'a>;
+
+/// an element with its size, and the sizes of its children
+#[derive(Clone)]
+pub(crate) struct Measured<'a> {
+    element: &'a CliElement,
+    pub width: usize,
+    pub height: usize,
+    /// the element has a border on every side,
+    /// so it can share its border with the frame next to it
+    framed: bool,
+    /// the inner elements, or the cells of a grid
+    children: Vec<Measured<'a>>,
+    /// the rows of a grid
+    rows: Vec<Vec<Placed>>,
+    /// the offsets of the layers of an overlay
+    offsets: Vec<(usize, usize)>,
+}
+
+impl<'a> Measured<'a> {
+    pub fn new(element: &'a CliElement) -> Self {
+        Self::measure(element, false).expect("only plain elements are left out")
+    }
+
+    /// measured without the elements which show only colors, such as pictures and
+    /// lines of colored spaces, none when nothing is left
+    pub fn plain(element: &'a CliElement) -> Option<Self> {
+        Self::measure(element, true)
+    }
+
+    fn measure(element: &'a CliElement, plain: bool) -> Option<Self> {
+        let children = |inner: &'a [CliElement]| -> Vec<Self> {
+            inner
+                .iter()
+                .filter_map(|inner| Self::measure(inner, plain))
+                .collect()
+        };
+        let mut measured = Self {
+            element,
+            width: 0,
+            height: 0,
+            framed: false,
+            children: vec![],
+            rows: vec![],
+            offsets: vec![],
+        };
+        match element {
+            CliElement::Row { inner, settings } => {
+                measured.children = children(inner);
+                let children = &measured.children;
+                let spacing = settings.unwrap_or_default().spacing;
+                measured.width = children.iter().map(|child| child.width).sum::<usize>()
+                    + (children.len() + 1) * spacing;
+                if spacing == 0 {
+                    measured.width -= joins(children);
+                }
+                measured.height = children.iter().map(|child| child.height).max().unwrap_or(0);
+                measured.framed = spacing == 0
+                    && !children.is_empty()
+                    && children.iter().all(|child| child.framed)
+                    && children
+                        .iter()
+                        .all(|child| child.height == children[0].height);
+            }
+            CliElement::Column { inner } => {
+                measured.children = children(inner);
+                let children = &measured.children;
+                measured.width = children.iter().map(|child| child.width).max().unwrap_or(0);
+                measured.height =
+                    children.iter().map(|child| child.height).sum::<usize>() - joins(children);
+                measured.framed = !children.is_empty() && children.iter().all(|child| child.framed);
+            }
+            CliElement::Single { inner, .. }
+                if plain
+                    && !inner.is_empty()
+                    && inner.iter().all(|line| {
+                        line.plain_text().trim().is_empty()
+                            && line.spans.iter().any(|span| span.style.bg.is_some())
+                    }) =>
+            {
+                return None;
+            }
+            CliElement::Single { inner, .. } => {
+                measured.width = inner.iter().map(Line::width).max().unwrap_or(0);
+                measured.height = inner.len();
+            }
+            CliElement::Template { template, data, .. } => {
+                let lines = template.render(data);
+                measured.width = lines.iter().map(Line::width).max().unwrap_or(0);
+                measured.height = lines.len();
+            }
+            CliElement::EmptyBlock => measured.height = 1,
+            CliElement::Constrained {
+                inner,
+                max_width,
+                overflow,
+            } => {
+                let child = Self::measure(inner, plain)?;
+                measured.width = child.width.min(*max_width);
+                // a wrapped line can take more lines, which are counted without being kept
+                measured.height = match overflow {
+                    Overflow::Wrap => constrain(child.clone(), *max_width, *overflow).0.count(),
+                    Overflow::Ellipsis | Overflow::Clip => child.height,
+                };
+                measured.children = vec![child];
+            }
+            CliElement::Frame { inner, settings } => {
+                let child = Self::measure(inner, plain)?;
+                if shares_border(&child, settings) {
+                    measured.width = child.width;
+                    measured.height = child.height;
+                } else {
+                    measured.width =
+                        (child.width + 2 * settings.padding).max(title_width(settings)) + 2;
+                    measured.height = child.height + 2;
+                }
+                measured.framed = true;
+                measured.children = vec![child];
+            }
+            CliElement::Table { rows, settings } => {
+                let value_width = rows
+                    .iter()
+                    .flat_map(|(_, value)| value.split('\n'))
+                    .map(display_width)
+                    .max()
+                    .unwrap_or(0);
+                measured.width = key_width(rows) + display_width(&settings.separator) + value_width;
+                measured.height = rows
+                    .iter()
+                    .map(|(key, value)| key.split('\n').count().max(value.split('\n').count()))
+                    .sum();
+            }
+            CliElement::Image { .. } if plain => return None,
+            CliElement::Image { width, height, .. } => {
+                measured.width = *width;
+                measured.height = *height;
+            }
+            CliElement::Gauge { settings, .. } => {
+                measured.width = gauge_width(settings);
+                measured.height = 1;
+            }
+            CliElement::Sparkline { values, .. } => {
+                measured.width = values.len();
+                measured.height = 1;
+            }
+            CliElement::BarChart { bars, settings } => {
+                measured.width = bar_chart(bars, settings)
+                    .iter()
+                    .map(Line::width)
+                    .max()
+                    .unwrap_or(0);
+                measured.height = bars.len() + if settings.axis { 2 } else { 0 };
+            }
+            // the colors would be left out anyway
+            CliElement::Colorized { inner, .. } if plain => return Self::measure(inner, plain),
+            CliElement::Colorized { inner, .. } => {
+                let child = Self::measure(inner, plain)?;
+                measured.width = child.width;
+                measured.height = child.height;
+                measured.children = vec![child];
+            }
+            CliElement::Grid { cells, settings } => {
+                let (children, spans): (Vec<Self>, Vec<usize>) = cells
+                    .iter()
+                    .filter_map(|cell| Some((Self::measure(&cell.element, plain)?, cell.span)))
+                    .unzip();
+                measured.children = children;
+                measured.rows = place(&spans, columns(settings).len());
+                measured.width = match settings.width {
+                    Some(width) => width,
+                    None => {
+                        let widths = measured.column_widths(settings, None);
+                        widths.iter().sum::<usize>() + gaps_width(settings, widths.len())
+                    }
+                };
+                measured.height = measured
+                    .rows
+                    .iter()
+                    .map(|row| measured.row_height(row))
+                    .sum::<usize>()
+                    + settings.row_gap * measured.rows.len().saturating_sub(1);
+            }
+            CliElement::Overlay { layers } => {
+                let (children, offsets): (Vec<Self>, Vec<(usize, usize)>) = layers
+                    .iter()
+                    .filter_map(|layer| {
+                        Some((Self::measure(&layer.element, plain)?, (layer.x, layer.y)))
+                    })
+                    .unzip();
+                let sides = children.iter().zip(&offsets);
+                measured.width = sides
+                    .clone()
+                    .map(|(child, (x, _))| x + child.width)
+                    .max()
+                    .unwrap_or(0);
+                measured.height = sides
+                    .map(|(child, (_, y))| y + child.height)
+                    .max()
+                    .unwrap_or(0);
+                measured.children = children;
+                measured.offsets = offsets;
+            }
+        }
+        Some(measured)
+    }
+
+    /// the lines of the element, every line padded to the draw width
+    pub fn into_lines(self, draw_width: usize) -> Lines<'a> {
+        match self.element {
+            CliElement::Table { rows, settings } => {
+                let key_width = key_width(rows);
+                // the lines of a key or a value after the first have no separator
+                let blank = spaces(display_width(&settings.separator));
+                Box::new(rows.iter().flat_map(move |(key, value)| {
+                    let keys: Vec<&str> = key.split('\n').collect();
+                    let values: Vec<&str> = value.split('\n').collect();
+                    let blank = blank.clone();
+                    (0..keys.len().max(values.len())).map(move |index| {
+                        let key = keys.get(index).copied().unwrap_or("");
+                        let separator = match index {
+                            0 => settings.separator.as_str(),
+                            _ => blank.as_str(),
+                        };
+                        let line = Line::new(vec![
+                            settings
+                                .key_style
+                                .paint(pad(key, key_width, settings.key_alignment)),
+                            settings.separator_style.paint(separator),
+                            Span::new(
+                                values.get(index).copied().unwrap_or(""),
+                                settings.value_style,
+                            ),
+                        ]);
+                        pad(&line.render(), draw_width, Alignment::Left)
+                    })
+                }))
+            }
+            CliElement::Frame { settings, .. } => {
+                let inner = self.only_child();
+                let vertical = settings.style.glyphs().vertical;
+                let top = top_edge(settings, draw_width);
+                let bottom = bottom_edge(settings, draw_width);
+                if shares_border(&inner, settings) {
+                    let last = inner.height.saturating_sub(1);
+                    return Box::new(inner.into_lines(draw_width).enumerate().map(
+                        move |(index, mut line)| {
+                            if index == 0 {
+                                line = merge_line_over(&top, &line);
+                            }
+                            if index == last {
+                                line = merge_line_over(&bottom, &line);
+                            }
+                            if index == 0 || index == last {
+                                return line;
+                            }
+                            let mut chars = line.chars();
+                            let first = chars.next().unwrap_or(' ');
+                            let end = chars.next_back().unwrap_or(' ');
+                            format!(
+                                "{}{}{}",
+                                merge_over(vertical, first),
+                                chars.as_str(),
+                                merge_over(vertical, end)
+                            )
+                        },
+                    ));
+                }
+                let padding = spaces(settings.padding);
+                let inner_width = draw_width.saturating_sub(2 + 2 * settings.padding);
+                Box::new(
+                    iter::once(top)
+                        .chain(inner.into_lines(inner_width).map(move |line| {
+                            format!("{vertical}{padding}{line}{padding}{vertical}")
+                        }))
+                        .chain(iter::once(bottom)),
+                )
+            }
+            CliElement::Constrained {
+                max_width,
+                overflow,
+                ..
+            } => {
+                let (lines, layout) = constrain(self.only_child(), *max_width, *overflow);
+                Box::new(lines.map(move |line| pad(&line, draw_width, layout)))
+            }
+            CliElement::EmptyBlock => Box::new(iter::once(spaces(draw_width))),
+            CliElement::Colorized { colorize, .. } => {
+                let size = (draw_width, self.height);
+                Box::new(self.only_child().into_lines(draw_width).enumerate().map(
+                    move |(y, line)| {
+                        colorize_line(&Line::from_ansi(&line), colorize, y, size).render()
+                    },
+                ))
+            }
+            CliElement::Grid { settings, .. } => {
+                let stretch = (draw_width > self.width).then_some(draw_width);
+                let widths = self.column_widths(settings, stretch);
+                let gap = spaces(settings.column_gap);
+                let heights: Vec<usize> =
+                    self.rows.iter().map(|row| self.row_height(row)).collect();
+                let mut cells: Vec<Option<Measured>> =
+                    self.children.into_iter().map(Some).collect();
+                Box::new(self.rows.into_iter().zip(heights).enumerate().flat_map(
+                    move |(index, (row, height))| {
+                        let mut output =
+                            vec![spaces(draw_width); if index > 0 { settings.row_gap } else { 0 }];
+                        let mut lines = vec![String::new(); height];
+                        let mut column = 0;
+                        for placed in &row {
+                            let width = span_width(&widths, placed, settings.column_gap);
+                            let cell = cells[placed.index]
+                                .take()
+                                .expect("every cell is placed once")
+                                .cell_lines(width);
+                            for (index, line) in lines.iter_mut().enumerate() {
+                                if column > 0 {
+                                    line.push_str(&gap);
+                                }
+                                match cell.get(index) {
+                                    Some(cell) => line.push_str(cell),
+                                    None => line.push_str(&spaces(width)),
+                                }
+                            }
+                            column = placed.column + placed.span;
+                        }
+                        output.extend(lines.iter().map(|line| {
+                            pad(&truncate(line, draw_width, ""), draw_width, Alignment::Left)
+                        }));
+                        output
+                    },
+                ))
+            }
+            CliElement::Overlay { .. } => Box::new(OverlayLines {
+                layers: self
+                    .children
+                    .into_iter()
+                    .zip(self.offsets)
+                    .map(|(child, (x, y))| {
+                        let width = child.width;
+                        OverlayLayer {
+                            x,
+                            y,
+                            height: child.height,
+                            lines: child.into_lines(width),
+                        }
+                    })
+                    .collect(),
+                index: 0,
+                height: self.height,
+                draw_width,
+            }),
+            CliElement::Gauge { ratio, settings } => Box::new(iter::once(pad(
+                &gauge(*ratio, settings).render(),
+                draw_width,
+                Alignment::Left,
+            ))),
+            CliElement::Sparkline { values, settings } => Box::new(iter::once(pad(
+                &sparkline(values, settings).render(),
+                draw_width,
+                Alignment::Left,
+            ))),
+            CliElement::BarChart { bars, settings } => Box::new(
+                bar_chart(bars, settings)
+                    .into_iter()
+                    .map(move |line| pad(&line.render(), draw_width, Alignment::Left)),
+            ),
+            CliElement::Image {
+                image,
+                protocol,
+                width,
+                height,
+            } => Box::new(
+                image
+                    .cells(*protocol, *width, *height)
+                    .into_iter()
+                    .map(move |line| pad(&line.render(), draw_width, Alignment::Left)),
+            ),
+            CliElement::Single { inner, layout } => Box::new(
+                inner
+                    .iter()
+                    .map(move |line| pad(&line.render(), draw_width, *layout)),
+            ),
+            CliElement::Template {
+                template,
+                data,
+                layout,
+            } => Box::new(
+                template
+                    .render(data)
+                    .into_iter()
+                    .map(move |line| pad(&line.render(), draw_width, *layout)),
+            ),
+            CliElement::Column { .. } => Box::new(ColumnLines {
+                children: self.children.into_iter(),
+                current: Box::new(iter::empty()),
+                framed: false,
+                held: None,
+                draw_width,
+            }),
+            CliElement::Row { settings, .. } => {
+                let settings = settings.unwrap_or_default();
+                let height = self.height;
+                let mut framed = false;
+                let children = self
+                    .children
+                    .into_iter()
+                    .map(|child| {
+                        let join = settings.spacing == 0 && framed && child.framed;
+                        framed = child.framed;
+                        let blank = height - child.height;
+                        let top = match settings.vertical {
+                            VerticalAlignment::Top => 0,
+                            VerticalAlignment::Middle => blank / 2,
+                            VerticalAlignment::Bottom => blank,
+                        };
+                        let width = child.width;
+                        RowChild {
+                            top,
+                            height: child.height,
+                            empty: spaces(width),
+                            join,
+                            lines: child.into_lines(width),
+                        }
+                    })
+                    .collect();
+                Box::new(RowLines {
+                    children,
+                    spacing: spaces(settings.spacing),
+                    fill: spaces(draw_width.saturating_sub(self.width)),
+                    index: 0,
+                    height,
+                })
+            }
+        }
+    }
+
+    /// the inner element of a frame, of a constrained or of a colorized element
+    fn only_child(self) -> Self {
+        self.children
+            .into_iter()
+            .next()
+            .expect("the element is measured with its inner element")
+    }
+
+    /// the widths of the columns of a grid, the grid fills the draw width when it stretches
+    fn column_widths(&self, settings: &GridSettings, draw_width: Option<usize>) -> Vec<usize> {
+        let sizes: Vec<usize> = self.children.iter().map(|child| child.width).collect();
+        let total = settings.width.or(draw_width.filter(|_| settings.stretch));
+        column_widths(settings, &self.rows, &sizes, total)
+    }
+
+    /// the height of a row of a grid, the height of its highest cell
+    fn row_height(&self, row: &[Placed]) -> usize {
+        row.iter()
+            .map(|placed| self.children[placed.index].height)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// the lines of a cell, cut with `…` when the element is wider than the cell
+    fn cell_lines(self, width: usize) -> Vec<String> {
+        if self.width <= width {
+            return self.into_lines(width).collect();
+        }
+        let (lines, layout) = constrain(self, width, Overflow::Ellipsis);
+        lines.map(|line| pad(&line, width, layout)).collect()
+    }
+}
+
+/// the lines of the children of a column one after the other, the last line of a
+/// child is held back until it is known whether the next one shares its border
+struct ColumnLines<'a> {
+    children: std::vec::IntoIter<Measured<'a>>,
+    current: Lines<'a>,
+    /// the child which is drawn is framed
+    framed: bool,
+    held: Option<String>,
+    draw_width: usize,
+}
+
+impl Iterator for ColumnLines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            if let Some(line) = self.current.next() {
+                match self.held.replace(line) {
+                    Some(held) => return Some(held),
+                    None => continue,
+                }
+            }
+            let Some(child) = self.children.next() else {
+                return self.held.take();
+            };
+            let join = self.framed && child.framed;
+            self.framed = child.framed;
+            self.current = child.into_lines(self.draw_width);
+            if join
+                && let Some(held) = self.held.take()
+                && let Some(first) = self.current.next()
+            {
+                self.held = Some(merge_line(&held, &first));
+            }
+        }
+    }
+}
+
+/// a child of a row, and the lines it takes in the row
+struct RowChild<'a> {
+    top: usize,
+    height: usize,
+    /// the line above and under the child
+    empty: String,
+    /// the child shares its border with the one before
+    join: bool,
+    lines: Lines<'a>,
+}
+
+/// the lines of a row, a line of every child side by side
+struct RowLines<'a> {
+    children: Vec<RowChild<'a>>,
+    spacing: String,
+    /// the spaces after the last child, up to the draw width
+    fill: String,
+    index: usize,
+    height: usize,
+}
+
+impl Iterator for RowLines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.index >= self.height {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+        let mut line = self.spacing.clone();
+        for child in &mut self.children {
+            let content = if index < child.top || index >= child.top + child.height {
+                None
+            } else {
+                child.lines.next()
+            };
+            let content = content.as_ref().unwrap_or(&child.empty);
+            if child.join {
+                merge_edge(&mut line, content);
+            } else {
+                line.push_str(content);
+            }
+            line.push_str(&self.spacing);
+        }
+        line.push_str(&self.fill);
+        Some(line)
+    }
+}
+
+/// a layer of an overlay, and where its lines are
+struct OverlayLayer<'a> {
+    x: usize,
+    y: usize,
+    height: usize,
+    lines: Lines<'a>,
+}
+
+/// the lines of an overlay, a line of every layer drawn over each other
+struct OverlayLines<'a> {
+    layers: Vec<OverlayLayer<'a>>,
+    index: usize,
+    height: usize,
+    draw_width: usize,
+}
+
+impl Iterator for OverlayLines<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.index >= self.height {
+            return None;
+        }
+        let index = self.index;
+        self.index += 1;
+        let mut line = vec![];
+        for layer in &mut self.layers {
+            if index >= layer.y
+                && index < layer.y + layer.height
+                && let Some(text) = layer.lines.next()
+            {
+                put(&mut line, cells(&text), layer.x);
+            }
+        }
+        Some(pad(&render(&line), self.draw_width, Alignment::Left))
+    }
+}
+
+/// the lines of the element cut to the max width as the overflow says, before padding,
+/// and how to pad them
+fn constrain(inner: Measured, max_width: usize, overflow: Overflow) -> (Lines, Alignment) {
+    let (lines, layout): (Lines, Alignment) = match inner.element {
+        CliElement::Single { inner, layout } => (Box::new(inner.iter().map(Line::render)), *layout),
+        CliElement::Template {
+            template,
+            data,
+            layout,
+        } => (
+            Box::new(template.render(data).into_iter().map(|line| line.render())),
+            *layout,
+        ),
+        _ => {
+            let width = inner.width;
+            (
+                Box::new(
+                    inner
+                        .into_lines(width)
+                        .map(|line| line.trim_end_matches(' ').to_string()),
+                ),
+                Alignment::Left,
+            )
+        }
+    };
+    let lines: Lines = match overflow {
+        Overflow::Ellipsis => Box::new(lines.map(move |line| truncate(&line, max_width, "…"))),
+        Overflow::Clip => Box::new(lines.map(move |line| truncate(&line, max_width, ""))),
+        Overflow::Wrap => Box::new(lines.flat_map(move |line| wrap(&line, max_width))),
+    };
+    (lines, layout)
+}
+
+/// a frame without padding around a framed element draws its border over the inner one
+fn shares_border(inner: &Measured, settings: &FrameSettings) -> bool {
+    settings.padding == 0 && inner.framed && title_width(settings) + 2 <= inner.width
+}
+
+/// the count of neighbours which share their border
+fn joins(children: &[Measured]) -> usize {
+    children
+        .windows(2)
+        .filter(|pair| pair[0].framed && pair[1].framed)
+        .count()
+}
+
+/// the widest line of the keys, a key with line breaks takes many lines
+fn key_width(rows: &[(String, String)]) -> usize {
+    rows.iter()
+        .flat_map(|(key, _)| key.split('\n'))
+        .map(display_width)
+        .max()
+        .unwrap_or(0)
+}
+
+fn spaces(width: usize) -> String {
+    " ".repeat(width)
+}
+
+#[test]
+fn tst_measure() {
+    use crate::layout::RowSettings;
+    let mounts: Vec<(String, String)> = (0..5000)
+        .map(|index| (format!("/mnt/disk{index}"), format!("{index} GiB")))
+        .collect();
+    let mut tree = CliElement::print_frame(
+        CliElement::Table {
+            rows: mounts,
+            settings: Default::default(),
+        },
+        FrameSettings::default(),
+    );
+    for _ in 0..200 {
+        tree = CliElement::Row {
+            inner: vec![CliElement::print_single(&["|"], Alignment::Left), tree],
+            settings: Some(RowSettings::default()),
+        };
+    }
+    let measured = Measured::new(&tree);
+    assert_eq!((measured.width, measured.height), (225, 5002));
+    let width = measured.width;
+    let mut lines = measured.into_lines(width);
+    assert_eq!(
+        lines.next().unwrap(),
+        format!("{}┌{}┐", "|".repeat(200), "─".repeat(23))
+    );
+    assert_eq!(
+        lines.next().unwrap(),
+        format!("{}│/mnt/disk0   : 0 GiB   │", " ".repeat(200))
+    );
+    assert_eq!(lines.count(), 5000);
+}
+
+#[test]
+fn tst_constrained() {
+    use crate::overlay::Layer;
+    // the overlay reaches far below, drawing all of its lines would never end
+    let below = 1 << 40;
+    let column = CliElement::Column {
+        inner: vec![
+            CliElement::print_single(&["cpu: Intel Core i7"], Alignment::Left),
+            CliElement::print_overlay(vec![
+                Layer::new(CliElement::print_single(&["x"], Alignment::Left)).offset(0, below),
+            ]),
+        ],
+    };
+    let tree = CliElement::print_constrained(column, 8, Overflow::Ellipsis);
+    assert_eq!((tree.width(), tree.height()), (8, below + 2));
+    let mut lines = tree.lines();
+    assert_eq!(lines.next().unwrap(), "cpu: In…");
+    assert_eq!(lines.next().unwrap(), spaces(8));
+}
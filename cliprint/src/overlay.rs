@@ -0,0 +1,189 @@
+//! elements drawn over each other, every layer at its own offset
+//!
+//! the later layers are drawn over the earlier ones, a space without background
+//! is transparent, so the layer below shows through it
+//! ```
+//! use cliprint::elements::CliElement;
+//! use cliprint::layout::Alignment;
+//! use cliprint::overlay::Layer;
+//! let a = CliElement::print_overlay(vec![
+//!     Layer::new(CliElement::print_single(&["#####", "#####"], Alignment::Left)),
+//!     Layer::new(CliElement::print_single(&["OS  x"], Alignment::Left)).offset(1, 1),
+//! ]);
+//! assert_eq!(a.render_lines(), vec!["##### ", "#OS##x"]);
+//! ```
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::elements::CliElement;
+use crate::style::{Line, Span, Style};
+use crate::text::{Token, grapheme_width, tokens};
+
+/// an element in an overlay and its offset from the top left corner
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layer {
+    pub element: CliElement,
+    /// the columns left of the layer
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub x: usize,
+    /// the lines above the layer
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub y: usize,
+}
+
+impl Layer {
+    /// a layer in the top left corner
+    pub fn new(element: CliElement) -> Self {
+        Self {
+            element,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn offset(mut self, x: usize, y: usize) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+}
+
+impl From<CliElement> for Layer {
+    fn from(element: CliElement) -> Self {
+        Self::new(element)
+    }
+}
+
+/// a column of a line
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Cell {
+    /// nothing is drawn in the column, the layer below shows through
+    Empty,
+    /// a grapheme with the escape sequences around it, such as links
+    Char { text: String, style: Style },
+    /// the second column of a wide grapheme
+    Wide,
+}
+
+/// the columns of the rendered line
+pub(crate) fn cells(line: &str) -> Vec<Cell> {
+    let mut cells = vec![];
+    let mut escapes = String::new();
+    for span in Line::from_ansi(line).spans {
+        for token in tokens(&span.text) {
+            let text = match token {
+                Token::Escape(escape) => {
+                    escapes.push_str(escape);
+                    continue;
+                }
+                Token::Text(text) => text,
+            };
+            for grapheme in text.graphemes(true) {
+                if grapheme == " " && span.style.bg.is_none() && escapes.is_empty() {
+                    cells.push(Cell::Empty);
+                    continue;
+                }
+                let width = grapheme_width(grapheme);
+                cells.push(Cell::Char {
+                    text: std::mem::take(&mut escapes) + grapheme,
+                    style: span.style,
+                });
+                if width == 2 {
+                    cells.push(Cell::Wide);
+                }
+            }
+        }
+    }
+    // the escape sequences at the end, such as the end of a link, stay with the last grapheme
+    let last = cells.iter_mut().rev().find_map(|cell| match cell {
+        Cell::Char { text, .. } => Some(text),
+        _ => None,
+    });
+    if let Some(text) = last {
+        text.push_str(&escapes);
+    }
+    cells
+}
+
+/// draw the cells of a layer over the line from the column x, the wide graphemes
+/// which are cut in half become spaces
+pub(crate) fn put(line: &mut Vec<Cell>, layer: Vec<Cell>, x: usize) {
+    if line.len() < x + layer.len() {
+        line.resize(x + layer.len(), Cell::Empty);
+    }
+    for (index, cell) in layer.into_iter().enumerate() {
+        if cell == Cell::Empty {
+            continue;
+        }
+        let column = x + index;
+        match line[column] {
+            Cell::Wide => blank(&mut line[column - 1]),
+            Cell::Char { .. } if line.get(column + 1) == Some(&Cell::Wide) => {
+                blank(&mut line[column + 1])
+            }
+            _ => {}
+        }
+        line[column] = cell;
+    }
+}
+
+/// a space with the background of the cell
+fn blank(cell: &mut Cell) {
+    let style = match cell {
+        Cell::Char { style, .. } => Style {
+            bg: style.bg,
+            ..Style::default()
+        },
+        _ => Style::default(),
+    };
+    *cell = Cell::Char {
+        text: " ".to_string(),
+        style,
+    };
+}
+
+/// the line of the cells, the empty cells are spaces
+pub(crate) fn render(cells: &[Cell]) -> String {
+    let mut spans: Vec<Span> = vec![];
+    for cell in cells {
+        let (text, style) = match cell {
+            Cell::Empty => (" ", Style::default()),
+            Cell::Char { text, style } => (text.as_str(), *style),
+            Cell::Wide => continue,
+        };
+        match spans.last_mut() {
+            Some(last) if last.style == style => last.text.push_str(text),
+            _ => spans.push(Span::new(text, style)),
+        }
+    }
+    Line::new(spans).render()
+}
+
+#[test]
+fn tst_overlay() {
+    use crate::style::Color;
+    let red = Color::Red.paint("ab").style;
+    let mut line = cells("你好 c");
+    assert_eq!(line.len(), 6);
+    put(&mut line, cells("x"), 1);
+    assert_eq!(render(&line), " x好 c");
+    put(&mut line, cells("\x1b[31mab\x1b[0m"), 4);
+    assert_eq!(
+        line[4..],
+        [
+            Cell::Char {
+                text: "a".to_string(),
+                style: red
+            },
+            Cell::Char {
+                text: "b".to_string(),
+                style: red
+            }
+        ]
+    );
+    put(&mut line, cells("  \x1b[44m \x1b[0m"), 1);
+    assert_eq!(render(&line), " x \x1b[44m \x1b[0m\x1b[31mab\x1b[0m");
+    let link = "\x1b]8;;https://a\x1b\\l\x1b]8;;\x1b\\";
+    assert_eq!(render(&cells(link)), link);
+}
@@ -0,0 +1,229 @@
+//! elements made by futures, which are driven at the same time on the calling thread
+//! and laid out in the order they were given, so the slow sources of an output, such
+//! as D-Bus calls or commands, wait together instead of one after the other
+//!
+//! a producer which takes longer than its timeout, or fails, gives its fallback,
+//! or is left out when it has none
+//! ```
+//! use std::time::Duration;
+//!
+//! use cliprint::elements::CliElement;
+//! use cliprint::layout::Alignment;
+//! use cliprint::producer::Producer;
+//! let a = CliElement::print_column_async(vec![
+//!     Producer::spawn(|| {
+//!         std::thread::sleep(Duration::from_millis(20));
+//!         CliElement::print_single(&["slow"], Alignment::Left)
+//!     }),
+//!     Producer::new(async { CliElement::print_single(&["fast"], Alignment::Left) }),
+//!     Producer::new(std::future::pending())
+//!         .timeout(Duration::from_millis(50))
+//!         .fallback(CliElement::print_single(&["late"], Alignment::Left)),
+//! ]);
+//! assert_eq!(a.render_lines(), vec!["slow", "fast", "late"]);
+//! ```
+#[cfg(feature = "nightly")]
+use std::async_iter::AsyncIterator;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crate::elements::CliElement;
+
+/// a future which makes an element, or anything else which [resolve] collects
+pub struct Producer<'a, T = CliElement> {
+    /// none when the producer failed
+    future: Pin<Box<dyn Future<Output = Option<T>> + 'a>>,
+    timeout: Option<Duration>,
+    fallback: Option<T>,
+}
+
+impl<'a, T: 'a> Producer<'a, T> {
+    pub fn new(future: impl Future<Output = T> + 'a) -> Self {
+        Self {
+            future: Box::pin(async { Some(future.await) }),
+            timeout: None,
+            fallback: None,
+        }
+    }
+
+    /// the longest time to wait for the producer, from when [resolve] starts
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// what is given instead when the producer times out or fails
+    #[must_use]
+    pub fn fallback(mut self, fallback: T) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+}
+
+impl<T: Send + 'static> Producer<'static, T> {
+    /// the closure runs on a thread of its own, for the sources which block,
+    /// the producer fails when the closure panics
+    pub fn spawn(producer: impl FnOnce() -> T + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let waker = Arc::new(Mutex::new(None));
+        let wake = WakeOnDrop(waker.clone());
+        thread::spawn(move || {
+            // the locals are dropped in reverse, also when the closure panics, so the
+            // result is sent or the sender is dropped before the wake
+            let _wake = wake;
+            let sender = sender;
+            let _ = sender.send(producer());
+        });
+        Self {
+            future: Box::pin(Spawned { receiver, waker }),
+            timeout: None,
+            fallback: None,
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a> Producer<'a> {
+    /// the elements of the stream in a column
+    pub fn column(stream: impl AsyncIterator<Item = CliElement> + 'a) -> Self {
+        let mut stream = Box::pin(stream);
+        let mut inner = vec![];
+        Self::new(std::future::poll_fn(move |cx| {
+            loop {
+                match stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(element)) => inner.push(element),
+                    Poll::Ready(None) => {
+                        return Poll::Ready(CliElement::Column {
+                            inner: std::mem::take(&mut inner),
+                        });
+                    }
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+        }))
+    }
+}
+
+/// the result of a spawned closure
+struct Spawned<T> {
+    receiver: Receiver<T>,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<T> Future for Spawned<T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        *self.waker.lock().unwrap_or_else(|error| error.into_inner()) = Some(cx.waker().clone());
+        match self.receiver.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+        }
+    }
+}
+
+/// wakes the future of a spawned closure when the closure returns or panics
+struct WakeOnDrop(Arc<Mutex<Option<Waker>>>);
+
+impl Drop for WakeOnDrop {
+    fn drop(&mut self) {
+        let waker = self
+            .0
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+            .take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// wakes the thread which drives the producers
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// drive the producers at the same time until all of them are finished or timed out,
+/// the results are in the order of the producers
+pub fn resolve<T>(producers: Vec<Producer<T>>) -> Vec<T> {
+    let start = Instant::now();
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut results: Vec<Option<T>> = producers.iter().map(|_| None).collect();
+    let mut pending: Vec<Option<Producer<T>>> = producers.into_iter().map(Some).collect();
+    loop {
+        let mut deadline: Option<Instant> = None;
+        for (index, slot) in pending.iter_mut().enumerate() {
+            let Some(producer) = slot else {
+                continue;
+            };
+            let end = producer.timeout.map(|timeout| start + timeout);
+            match producer.future.as_mut().poll(&mut cx) {
+                Poll::Ready(Some(value)) => results[index] = Some(value),
+                Poll::Ready(None) => results[index] = producer.fallback.take(),
+                Poll::Pending => match end {
+                    Some(end) if Instant::now() >= end => {
+                        results[index] = producer.fallback.take();
+                    }
+                    Some(end) => {
+                        deadline = Some(deadline.map_or(end, |deadline| deadline.min(end)));
+                        continue;
+                    }
+                    None => continue,
+                },
+            }
+            *slot = None;
+        }
+        if pending.iter().all(Option::is_none) {
+            break;
+        }
+        match deadline {
+            Some(deadline) => {
+                thread::park_timeout(deadline.saturating_duration_since(Instant::now()))
+            }
+            None => thread::park(),
+        }
+    }
+    results.into_iter().flatten().collect()
+}
+
+#[test]
+fn tst_resolve() {
+    use std::sync::Barrier;
+    // the barrier lets both through only when they wait at the same time
+    let barrier = Arc::new(Barrier::new(2));
+    let together = |value: &'static str| {
+        let barrier = Arc::clone(&barrier);
+        Producer::spawn(move || {
+            barrier.wait();
+            value
+        })
+        .timeout(Duration::from_secs(10))
+        .fallback("alone")
+    };
+    let values = resolve(vec![
+        together("a"),
+        together("b"),
+        Producer::new(async { "c" }),
+        Producer::new(std::future::pending()).timeout(Duration::from_millis(20)),
+        Producer::spawn(|| panic!("no source")).fallback("e"),
+        Producer::spawn(|| {
+            thread::sleep(Duration::from_secs(10));
+            "f"
+        })
+        .timeout(Duration::from_millis(10))
+        .fallback("g"),
+    ]);
+    assert_eq!(values, vec!["a", "b", "c", "e", "g"]);
+}
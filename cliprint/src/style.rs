@@ -0,0 +1,728 @@
+//! styled text, the escape sequences are made only when it is rendered
+//! ```
+//! use cliprint::style::{Color, Line, Style};
+//! let line = Line::from(vec![Color::Cyan.bold().paint("OS"), Style::new().paint(": Arch")]);
+//! assert_eq!(line.width(), 8);
+//! assert_eq!(line.render(), "\x1b[1;36mOS\x1b[0m: Arch");
+//! assert_eq!(line.plain_text(), "OS: Arch");
+//! ```
+use std::fmt::Write;
+
+use crate::terminal::ColorSupport;
+use crate::text::{Token, display_width, tokens};
+
+/// the colors of the terminal, the sixteen colors of the palette,
+/// the 256 colors and the truecolors
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// the rgb of the sixteen colors, as xterm shows them
+const PALETTE_RGB: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// the levels of the 6x6x6 color cube in the 256 colors
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let dr = r1 as i32 - r2 as i32;
+    let dg = g1 as i32 - g2 as i32;
+    let db = b1 as i32 - b2 as i32;
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+const PALETTE: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::White,
+    Color::BrightBlack,
+    Color::BrightRed,
+    Color::BrightGreen,
+    Color::BrightYellow,
+    Color::BrightBlue,
+    Color::BrightMagenta,
+    Color::BrightCyan,
+    Color::BrightWhite,
+];
+
+impl Color {
+    /// the index in the palette of the sixteen colors
+    pub fn palette_index(self) -> Option<u8> {
+        PALETTE
+            .iter()
+            .position(|color| *color == self)
+            .map(|index| index as u8)
+    }
+
+    /// the color at the index of the palette of the sixteen colors
+    pub fn from_palette_index(index: u8) -> Option<Self> {
+        PALETTE.get(index as usize).copied()
+    }
+
+    /// the rgb the color is shown with
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Fixed(index @ 0..=15) => PALETTE_RGB[index as usize],
+            Color::Fixed(index @ 16..=231) => {
+                let index = index - 16;
+                (
+                    CUBE_LEVELS[(index / 36) as usize],
+                    CUBE_LEVELS[(index / 6 % 6) as usize],
+                    CUBE_LEVELS[(index % 6) as usize],
+                )
+            }
+            Color::Fixed(index) => {
+                let gray = 8 + 10 * (index - 232);
+                (gray, gray, gray)
+            }
+            color => PALETTE_RGB[color.palette_index().unwrap_or(7) as usize],
+        }
+    }
+
+    /// the nearest color of the 256 colors
+    fn to_fixed(self) -> Color {
+        let rgb = self.to_rgb();
+        let level = |value: u8| {
+            CUBE_LEVELS
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, level)| (**level as i32 - value as i32).abs())
+                .map(|(index, _)| index as u8)
+                .unwrap_or(0)
+        };
+        let (r, g, b) = (level(rgb.0), level(rgb.1), level(rgb.2));
+        let cube = Color::Fixed(16 + 36 * r + 6 * g + b);
+        let average = ((rgb.0 as u16 + rgb.1 as u16 + rgb.2 as u16) / 3) as u8;
+        let gray = Color::Fixed(232 + (average.saturating_sub(3) / 10).min(23));
+        if distance(gray.to_rgb(), rgb) < distance(cube.to_rgb(), rgb) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    /// the nearest color of the sixteen colors
+    fn to_palette(self) -> Color {
+        let rgb = self.to_rgb();
+        PALETTE_RGB
+            .iter()
+            .zip(PALETTE)
+            .min_by_key(|(palette, _)| distance(**palette, rgb))
+            .map(|(_, color)| color)
+            .unwrap_or(Color::White)
+    }
+
+    /// the color the terminal can show, none when it can not show colors
+    /// ```
+    /// use cliprint::style::Color;
+    /// use cliprint::terminal::ColorSupport;
+    /// assert_eq!(
+    ///     Color::Rgb(255, 0, 0).downgrade(ColorSupport::Ansi256),
+    ///     Some(Color::Fixed(196))
+    /// );
+    /// assert_eq!(
+    ///     Color::Rgb(250, 10, 10).downgrade(ColorSupport::Ansi16),
+    ///     Some(Color::BrightRed)
+    /// );
+    /// ```
+    pub fn downgrade(self, support: ColorSupport) -> Option<Color> {
+        match (support, self) {
+            (ColorSupport::Plain | ColorSupport::None, _) => None,
+            (ColorSupport::TrueColor, color) => Some(color),
+            (ColorSupport::Ansi256, Color::Rgb(..)) => Some(self.to_fixed()),
+            (ColorSupport::Ansi256, color) => Some(color),
+            (ColorSupport::Ansi16, Color::Fixed(index)) if index < 16 => {
+                Color::from_palette_index(index)
+            }
+            (ColorSupport::Ansi16, Color::Rgb(..) | Color::Fixed(_)) => Some(self.to_palette()),
+            (ColorSupport::Ansi16, color) => Some(color),
+        }
+    }
+
+    /// the sgr parameters of the color, background uses the codes of the background
+    fn write_sgr(self, output: &mut String, background: bool) {
+        let offset = if background { 10 } else { 0 };
+        match self {
+            Color::Fixed(index) => write!(output, "{};5;{}", 38 + offset, index),
+            Color::Rgb(r, g, b) => write!(output, "{};2;{};{};{}", 38 + offset, r, g, b),
+            color => {
+                let index = color.palette_index().unwrap_or(7);
+                let base = if index < 8 { 30 } else { 90 - 8 };
+                write!(output, "{}", base + offset + index)
+            }
+        }
+        .expect("write to a String never fails");
+    }
+
+    /// a style with the color as foreground
+    #[must_use]
+    pub fn normal(self) -> Style {
+        Style::new().fg(self)
+    }
+
+    /// a bold style with the color as foreground
+    #[must_use]
+    pub fn bold(self) -> Style {
+        Style::new().fg(self).bold()
+    }
+
+    /// paint the text with the color
+    #[must_use]
+    pub fn paint(self, text: impl Into<String>) -> Span {
+        self.normal().paint(text)
+    }
+}
+
+/// how a span looks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub blink: bool,
+    /// the foreground and the background are swapped
+    pub reverse: bool,
+    pub hidden: bool,
+    pub strikethrough: bool,
+}
+
+impl Style {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+
+    #[must_use]
+    pub fn on(mut self, color: Color) -> Self {
+        self.bg = Some(color);
+        self
+    }
+
+    #[must_use]
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    #[must_use]
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    #[must_use]
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    #[must_use]
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    #[must_use]
+    pub fn blink(mut self) -> Self {
+        self.blink = true;
+        self
+    }
+
+    #[must_use]
+    pub fn reverse(mut self) -> Self {
+        self.reverse = true;
+        self
+    }
+
+    #[must_use]
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    #[must_use]
+    pub fn strikethrough(mut self) -> Self {
+        self.strikethrough = true;
+        self
+    }
+
+    /// the style with the colors the terminal can show, no style at all when it is plain
+    #[must_use]
+    pub fn downgrade(self, support: ColorSupport) -> Self {
+        if support == ColorSupport::Plain {
+            return Self::default();
+        }
+        Self {
+            fg: self.fg.and_then(|color| color.downgrade(support)),
+            bg: self.bg.and_then(|color| color.downgrade(support)),
+            ..self
+        }
+    }
+
+    /// the style shows the text as it is
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// paint the text with the style
+    #[must_use]
+    pub fn paint(self, text: impl Into<String>) -> Span {
+        Span {
+            text: text.into(),
+            style: self,
+        }
+    }
+
+    /// the escape sequence which turns the style on
+    pub fn prefix(&self) -> String {
+        if self.is_plain() {
+            return String::new();
+        }
+        let mut params = String::new();
+        for (on, code) in [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.blink, "5"),
+            (self.reverse, "7"),
+            (self.hidden, "8"),
+            (self.strikethrough, "9"),
+        ] {
+            if on {
+                params.push_str(code);
+                params.push(';');
+            }
+        }
+        if let Some(fg) = self.fg {
+            fg.write_sgr(&mut params, false);
+            params.push(';');
+        }
+        if let Some(bg) = self.bg {
+            bg.write_sgr(&mut params, true);
+            params.push(';');
+        }
+        params.pop();
+        format!("\x1b[{}m", params)
+    }
+
+    /// the escape sequence which turns the style off
+    pub fn suffix(&self) -> &'static str {
+        if self.is_plain() { "" } else { "\x1b[0m" }
+    }
+
+    /// change the style with the parameters of a sgr escape sequence
+    fn apply_sgr(&mut self, params: &str) {
+        let mut params = params
+            .split([';', ':'])
+            .map(|param| param.parse::<u16>().unwrap_or(0));
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                5 | 6 => self.blink = true,
+                7 => self.reverse = true,
+                8 => self.hidden = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                25 => self.blink = false,
+                27 => self.reverse = false,
+                28 => self.hidden = false,
+                29 => self.strikethrough = false,
+                30..=37 => self.fg = Color::from_palette_index(param as u8 - 30),
+                90..=97 => self.fg = Color::from_palette_index(param as u8 - 90 + 8),
+                40..=47 => self.bg = Color::from_palette_index(param as u8 - 40),
+                100..=107 => self.bg = Color::from_palette_index(param as u8 - 100 + 8),
+                39 => self.fg = None,
+                49 => self.bg = None,
+                38 | 48 => {
+                    let color = match params.next() {
+                        Some(5) => params.next().map(|index| Color::Fixed(index as u8)),
+                        Some(2) => {
+                            let r = params.next().unwrap_or(0) as u8;
+                            let g = params.next().unwrap_or(0) as u8;
+                            let b = params.next().unwrap_or(0) as u8;
+                            Some(Color::Rgb(r, g, b))
+                        }
+                        _ => None,
+                    };
+                    if param == 38 {
+                        self.fg = color;
+                    } else {
+                        self.bg = color;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// a piece of text with one style
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+impl Span {
+    #[must_use]
+    pub fn new(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+        }
+    }
+
+    /// a span without style
+    #[must_use]
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(text, Style::default())
+    }
+}
+
+/// a line of styled spans
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "LineRepr", into = "LineRepr")
+)]
+pub struct Line {
+    pub spans: Vec<Span>,
+}
+
+impl Line {
+    #[must_use]
+    pub fn new(spans: Vec<Span>) -> Self {
+        Self { spans }
+    }
+
+    /// read the sgr escape sequences of the str into styles,
+    /// other escape sequences are kept in the text
+    /// ```
+    /// use cliprint::style::{Color, Line, Span};
+    /// let line = Line::from_ansi("\x1b[1;36mname\x1b[0m: marine");
+    /// assert_eq!(
+    ///     line.spans,
+    ///     vec![Color::Cyan.bold().paint("name"), Span::plain(": marine")]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_ansi(s: &str) -> Self {
+        let mut spans: Vec<Span> = vec![];
+        let mut style = Style::default();
+        for token in tokens(s) {
+            let text = match token {
+                Token::Escape(escape) if escape.starts_with("\x1b[") && escape.ends_with('m') => {
+                    style.apply_sgr(&escape[2..escape.len() - 1]);
+                    continue;
+                }
+                Token::Escape(text) | Token::Text(text) => text,
+            };
+            match spans.last_mut() {
+                Some(last) if last.style == style => last.text.push_str(text),
+                _ => spans.push(Span::new(text, style)),
+            }
+        }
+        Self { spans }
+    }
+
+    /// the columns the line takes on the terminal
+    pub fn width(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|span| display_width(&span.text))
+            .sum()
+    }
+
+    /// the text of the line without any style
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// the line with the escape sequences of the styles
+    pub fn render(&self) -> String {
+        self.render_with(ColorSupport::TrueColor)
+    }
+
+    /// the same as [Line::render], the colors are downgraded to the support,
+    /// and the escape sequences in the text are left out when it is plain
+    pub fn render_with(&self, support: ColorSupport) -> String {
+        if support == ColorSupport::Plain {
+            return self
+                .spans
+                .iter()
+                .flat_map(|span| tokens(&span.text))
+                .filter_map(|token| match token {
+                    Token::Text(text) => Some(text),
+                    Token::Escape(_) => None,
+                })
+                .collect();
+        }
+        let mut output = String::new();
+        for span in &self.spans {
+            let style = span.style.downgrade(support);
+            output.push_str(&style.prefix());
+            output.push_str(&span.text);
+            output.push_str(style.suffix());
+        }
+        output
+    }
+}
+
+impl From<&str> for Line {
+    fn from(value: &str) -> Self {
+        Self::from_ansi(value)
+    }
+}
+
+impl From<String> for Line {
+    fn from(value: String) -> Self {
+        Self::from_ansi(&value)
+    }
+}
+
+impl From<Span> for Line {
+    fn from(value: Span) -> Self {
+        Self { spans: vec![value] }
+    }
+}
+
+impl From<Vec<Span>> for Line {
+    fn from(value: Vec<Span>) -> Self {
+        Self { spans: value }
+    }
+}
+
+/// a line is written as a string when it has no style, and as spans when it has
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum LineRepr {
+    Text(String),
+    Spans(Vec<Span>),
+}
+
+#[cfg(feature = "serde")]
+impl From<LineRepr> for Line {
+    fn from(value: LineRepr) -> Self {
+        match value {
+            LineRepr::Text(text) => Line::from_ansi(&text),
+            LineRepr::Spans(spans) => Line { spans },
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Line> for LineRepr {
+    fn from(value: Line) -> Self {
+        if value.spans.iter().all(|span| span.style.is_plain()) {
+            LineRepr::Text(value.plain_text())
+        } else {
+            LineRepr::Spans(value.spans)
+        }
+    }
+}
+
+/// the color of nu_ansi_term, none for the default color of the terminal
+#[cfg(feature = "color")]
+fn nu_color(value: nu_ansi_term::Color) -> Option<Color> {
+    use nu_ansi_term::Color as Nu;
+    Some(match value {
+        Nu::Black => Color::Black,
+        Nu::Red => Color::Red,
+        Nu::Green => Color::Green,
+        Nu::Yellow => Color::Yellow,
+        Nu::Blue => Color::Blue,
+        Nu::Purple | Nu::Magenta => Color::Magenta,
+        Nu::Cyan => Color::Cyan,
+        Nu::White => Color::White,
+        Nu::Default => return None,
+        Nu::DarkGray => Color::BrightBlack,
+        Nu::LightRed => Color::BrightRed,
+        Nu::LightGreen => Color::BrightGreen,
+        Nu::LightYellow => Color::BrightYellow,
+        Nu::LightBlue => Color::BrightBlue,
+        Nu::LightPurple | Nu::LightMagenta => Color::BrightMagenta,
+        Nu::LightCyan => Color::BrightCyan,
+        Nu::LightGray => Color::BrightWhite,
+        Nu::Fixed(index) => Color::Fixed(index),
+        Nu::Rgb(r, g, b) => Color::Rgb(r, g, b),
+    })
+}
+
+#[cfg(feature = "color")]
+impl From<nu_ansi_term::Style> for Style {
+    fn from(value: nu_ansi_term::Style) -> Self {
+        Self {
+            fg: value.foreground.and_then(nu_color),
+            bg: value.background.and_then(nu_color),
+            bold: value.is_bold,
+            dim: value.is_dimmed,
+            italic: value.is_italic,
+            underline: value.is_underline,
+            blink: value.is_blink,
+            reverse: value.is_reverse,
+            hidden: value.is_hidden,
+            strikethrough: value.is_strikethrough,
+        }
+    }
+}
+
+/// downgrade the colors of a rendered line
+pub(crate) fn downgrade_line(line: &str, support: ColorSupport) -> String {
+    if support == ColorSupport::TrueColor {
+        return line.to_string();
+    }
+    Line::from_ansi(line).render_with(support)
+}
+
+#[test]
+fn tst_downgrade() {
+    assert_eq!(Color::Fixed(244).to_rgb(), (128, 128, 128));
+    assert_eq!(
+        Color::Rgb(128, 128, 128).downgrade(ColorSupport::Ansi256),
+        Some(Color::Fixed(244))
+    );
+    assert_eq!(
+        Color::Fixed(21).downgrade(ColorSupport::Ansi16),
+        Some(Color::Blue)
+    );
+    assert_eq!(
+        downgrade_line("\x1b[1;38;2;0;205;205mOS\x1b[0m", ColorSupport::Ansi16),
+        "\x1b[1;36mOS\x1b[0m"
+    );
+    assert_eq!(
+        downgrade_line("\x1b[37;48;2;9;18;27m   \x1b[0m", ColorSupport::None),
+        "   "
+    );
+    assert_eq!(
+        downgrade_line(
+            "\x1b]8;;https://archlinux.org\x1b\\\x1b[1;36mArch\x1b[0m\x1b]8;;\x1b\\",
+            ColorSupport::Plain
+        ),
+        "Arch"
+    );
+}
+
+#[test]
+fn tst_ansi_round_trip() {
+    let line = Line::from_ansi("\x1b[1;38;2;1;2;3;48;5;200mab\x1b[22mc\x1b[0md\x1b[91me");
+    assert_eq!(
+        line.spans,
+        vec![
+            Style::new()
+                .fg(Color::Rgb(1, 2, 3))
+                .on(Color::Fixed(200))
+                .bold()
+                .paint("ab"),
+            Style::new()
+                .fg(Color::Rgb(1, 2, 3))
+                .on(Color::Fixed(200))
+                .paint("c"),
+            Span::plain("d"),
+            Color::BrightRed.paint("e"),
+        ]
+    );
+    assert_eq!(Line::from_ansi(&line.render()), line);
+    assert_eq!(line.width(), 5);
+    let line = Line::from_ansi("\x1b[5;7;8;9mab\x1b[25;28mc\x1b[27;29md");
+    assert_eq!(
+        line.spans,
+        vec![
+            Style::new()
+                .blink()
+                .reverse()
+                .hidden()
+                .strikethrough()
+                .paint("ab"),
+            Style::new().reverse().strikethrough().paint("c"),
+            Span::plain("d"),
+        ]
+    );
+    assert_eq!(Line::from_ansi(&line.render()), line);
+    assert_eq!(line.render(), "\x1b[5;7;8;9mab\x1b[0m\x1b[7;9mc\x1b[0md");
+}
+
+#[cfg(feature = "color")]
+#[test]
+fn tst_nu_ansi_term() {
+    use nu_ansi_term::Color as Nu;
+    assert_eq!(
+        Style::from(Nu::Default.on(Nu::Purple).reverse().strikethrough()),
+        Style::new().on(Color::Magenta).reverse().strikethrough()
+    );
+    assert_eq!(
+        Line::from_ansi(&Nu::Red.reverse().paint("a").to_string()).spans,
+        vec![Color::Red.normal().reverse().paint("a")]
+    );
+}
@@ -0,0 +1,167 @@
+//! draw lines to svg, as a screenshot of a terminal
+//!
+//! every cell has the same size, so the text is stretched to its cells, and the svg
+//! is the same for the same lines on every machine. Escape sequences which are no
+//! styles, such as pictures, are left out
+use crate::html::{colors, escape, hex};
+use crate::layout::SvgSettings;
+use crate::style::Line;
+use crate::text::{Token, display_width, tokens};
+
+/// the pixels of the title bar of the window
+const TITLE_BAR: usize = 28;
+
+/// the least pixels of a window with a title bar, the buttons fit in it
+const CHROME_WIDTH: usize = 72;
+
+/// the colors of the buttons in the title bar
+const BUTTONS: [&str; 3] = ["#ff5f56", "#ffbd2e", "#27c93f"];
+
+/// the lines in a svg image
+/// ```
+/// use cliprint::layout::SvgSettings;
+/// use cliprint::style::{Color, Line};
+/// use cliprint::svg::render_svg;
+/// let settings = SvgSettings {
+///     chrome: false,
+///     ..Default::default()
+/// };
+/// let svg = render_svg(&[Line::from(Color::Red.paint("ab"))], &settings);
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"38\" height=\"38\""));
+/// assert!(svg.contains("<text x=\"10\" y=\"23\" textLength=\"18\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#cd0000\">ab</text>"));
+/// ```
+pub fn render_svg(lines: &[Line], settings: &SvgSettings) -> String {
+    let theme = &settings.theme;
+    let columns = lines.iter().map(Line::width).max().unwrap_or(0);
+    let bar = if settings.chrome { TITLE_BAR } else { 0 };
+    let mut width = 2 * settings.padding + columns * settings.cell_width;
+    if settings.chrome {
+        width = width.max(CHROME_WIDTH);
+    }
+    let height = bar + 2 * settings.padding + lines.len() * settings.cell_height;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n"
+    );
+    let radius = if settings.chrome { " rx=\"6\"" } else { "" };
+    svg.push_str(&format!(
+        "<rect width=\"{width}\" height=\"{height}\"{radius} fill=\"{}\"/>\n",
+        hex(theme.background, None)
+    ));
+    if settings.chrome {
+        for (index, color) in BUTTONS.iter().enumerate() {
+            svg.push_str(&format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"{color}\"/>\n",
+                16 + 20 * index,
+                TITLE_BAR / 2
+            ));
+        }
+        if let Some(title) = &settings.title {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-family=\"{}\" \
+                 font-size=\"{}\" fill=\"{}\">{}</text>\n",
+                width / 2,
+                TITLE_BAR / 2 + settings.font_size / 3,
+                escape(&theme.font_family),
+                settings.font_size,
+                hex(theme.foreground, None),
+                escape(title)
+            ));
+        }
+    }
+    svg.push_str(&format!(
+        "<g font-family=\"{}\" font-size=\"{}\" xml:space=\"preserve\">\n",
+        escape(&theme.font_family),
+        settings.font_size
+    ));
+    for (row, line) in lines.iter().enumerate() {
+        let top = bar + settings.padding + row * settings.cell_height;
+        let baseline = top + settings.cell_height * 3 / 4;
+        let mut column = 0;
+        for span in &line.spans {
+            let text: String = tokens(&span.text)
+                .filter_map(|token| match token {
+                    Token::Text(text) => Some(text),
+                    Token::Escape(_) => None,
+                })
+                .collect();
+            let cells = display_width(&text);
+            let x = settings.padding + column * settings.cell_width;
+            column += cells;
+            let style = &span.style;
+            let (fg, bg) = colors(style, Some(theme));
+            if let Some(bg) = bg {
+                svg.push_str(&format!(
+                    "<rect x=\"{x}\" y=\"{top}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                    cells * settings.cell_width,
+                    settings.cell_height,
+                    hex(bg, Some(theme))
+                ));
+            }
+            if text.trim().is_empty() || style.hidden {
+                continue;
+            }
+            let mut attributes = format!(
+                " fill=\"{}\"",
+                hex(fg.unwrap_or(theme.foreground), Some(theme))
+            );
+            for (on, attribute) in [
+                (style.bold, " font-weight=\"bold\""),
+                (style.dim, " opacity=\"0.5\""),
+                (style.italic, " font-style=\"italic\""),
+            ] {
+                if on {
+                    attributes.push_str(attribute);
+                }
+            }
+            match (style.underline, style.strikethrough) {
+                (true, true) => attributes.push_str(" text-decoration=\"underline line-through\""),
+                (true, false) => attributes.push_str(" text-decoration=\"underline\""),
+                (false, true) => attributes.push_str(" text-decoration=\"line-through\""),
+                (false, false) => {}
+            }
+            svg.push_str(&format!(
+                "<text x=\"{x}\" y=\"{baseline}\" textLength=\"{}\" \
+                 lengthAdjust=\"spacingAndGlyphs\"{attributes}>{}</text>\n",
+                cells * settings.cell_width,
+                escape(&text)
+            ));
+        }
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+#[test]
+fn tst_render_svg() {
+    use crate::style::{Color, Span, Style};
+    let lines = [
+        Line::from("a<b"),
+        Line::from(vec![
+            Style::new().on(Color::Blue).paint("  "),
+            Span::plain(" "),
+            Color::Fixed(9).bold().paint("x"),
+        ]),
+    ];
+    let settings = SvgSettings {
+        cell_width: 10,
+        cell_height: 20,
+        padding: 4,
+        title: Some("marine".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        render_svg(&lines, &settings),
+        include_str!("../assert/screenshot.svg")
+    );
+    let reversed = render_svg(&[Line::from_ansi("\x1b[7;32mx\x1b[0m")], &settings);
+    let theme = &settings.theme;
+    assert!(reversed.contains(&format!(
+        "width=\"10\" height=\"20\" fill=\"{}\"/>",
+        hex(Color::Green, Some(theme))
+    )));
+    assert!(reversed.contains(&format!(
+        "fill=\"{}\">x</text>",
+        hex(theme.background, Some(theme))
+    )));
+}
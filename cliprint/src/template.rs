@@ -0,0 +1,407 @@
+//! lines made from a template and a map of values
+//!
+//! a placeholder is `{key}`, `{key|fallback}` or `{key|fallback:spec}`, the fallback is
+//! shown when the map has no value for the key, without fallback the placeholder is
+//! empty and still takes its width. The spec is
+//! `[align][width][.precision][.style]`, where align is `<`, `>` or `^`, and the style is
+//! words joined by `.`: `bold`, `dim`, `italic`, `underline`, `blink`, `reverse`,
+//! `hidden`, `strikethrough`, a color such as `cyan` or `bright_red` or `#ff8700`, and
+//! `on_` before a color for the background.
+//! `{{` and `}}` are the braces themselves
+//! ```
+//! use std::collections::HashMap;
+//! use cliprint::template::Template;
+//! let template = Template::parse("{key:bold.cyan}: {value:<8}|{gpu|none}").unwrap();
+//! let data = HashMap::from([("key", "OS"), ("value", "Arch")]);
+//! let lines = template.render(&data);
+//! assert_eq!(lines[0].render(), "\x1b[1;36mOS\x1b[0m: Arch    |none");
+//! ```
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display};
+use std::hash::{BuildHasher, Hash};
+use std::str::FromStr;
+
+use crate::layout::Alignment;
+use crate::style::{Color, Line, Span, Style};
+use crate::text::{pad, truncate};
+
+/// where the values of the placeholders come from
+pub trait TemplateData {
+    /// the value of the key, None when there is no such key
+    fn value(&self, key: &str) -> Option<String>;
+}
+
+impl<T: TemplateData + ?Sized> TemplateData for &T {
+    fn value(&self, key: &str) -> Option<String> {
+        (**self).value(key)
+    }
+}
+
+impl<K, V, S> TemplateData for HashMap<K, V, S>
+where
+    K: Borrow<str> + Hash + Eq,
+    V: Display,
+    S: BuildHasher,
+{
+    fn value(&self, key: &str) -> Option<String> {
+        self.get(key).map(ToString::to_string)
+    }
+}
+
+impl<K, V> TemplateData for BTreeMap<K, V>
+where
+    K: Borrow<str> + Ord,
+    V: Display,
+{
+    fn value(&self, key: &str) -> Option<String> {
+        self.get(key).map(ToString::to_string)
+    }
+}
+
+/// the keys are paths into the value, such as `cpu.name` or `gpus.0`,
+/// null is a missing value
+#[cfg(feature = "serde")]
+impl TemplateData for serde_json::Value {
+    fn value(&self, key: &str) -> Option<String> {
+        let mut value = self;
+        for part in key.split('.') {
+            value = match value {
+                serde_json::Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+                value => value.get(part)?,
+            };
+        }
+        match value {
+            serde_json::Value::Null => None,
+            serde_json::Value::String(text) => Some(text.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+}
+
+/// why a template cannot be parsed, the position is the byte in the template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// a `{` without its `}`
+    Unclosed(usize),
+    /// a `}` without its `{`
+    Unopened(usize),
+    /// a placeholder without key
+    EmptyKey(usize),
+    /// a spec which cannot be read
+    InvalidSpec(String),
+    /// a word of the style which is no style
+    UnknownStyle(String),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TemplateError::Unclosed(pos) => write!(f, "unclosed '{{' at {pos}"),
+            TemplateError::Unopened(pos) => write!(f, "unmatched '}}' at {pos}"),
+            TemplateError::EmptyKey(pos) => write!(f, "placeholder without key at {pos}"),
+            TemplateError::InvalidSpec(spec) => write!(f, "invalid spec '{spec}'"),
+            TemplateError::UnknownStyle(word) => write!(f, "unknown style '{word}'"),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Placeholder {
+    key: String,
+    fallback: Option<String>,
+    align: Alignment,
+    width: Option<usize>,
+    precision: Option<usize>,
+    style: Style,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Text(String),
+    Newline,
+    Placeholder(Placeholder),
+}
+
+/// a parsed template, it can be rendered with many maps,
+/// it is written in a layout file as the text of the template
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+pub struct Template {
+    source: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(template: &str) -> Result<Self, TemplateError> {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = template.char_indices().peekable();
+        while let Some((pos, char)) = chars.next() {
+            match char {
+                '{' if chars.next_if(|(_, c)| *c == '{').is_some() => text.push('{'),
+                '}' if chars.next_if(|(_, c)| *c == '}').is_some() => text.push('}'),
+                '}' => return Err(TemplateError::Unopened(pos)),
+                '\n' => {
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(Part::Newline);
+                }
+                '{' => {
+                    let Some(len) = template[pos..].find('}') else {
+                        return Err(TemplateError::Unclosed(pos));
+                    };
+                    if !text.is_empty() {
+                        parts.push(Part::Text(std::mem::take(&mut text)));
+                    }
+                    let body = &template[pos + 1..pos + len];
+                    parts.push(Part::Placeholder(parse_placeholder(body, pos)?));
+                    while chars.next_if(|(next, _)| *next <= pos + len).is_some() {}
+                }
+                char => text.push(char),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(Self {
+            source: template.to_string(),
+            parts,
+        })
+    }
+
+    /// the keys of the placeholders, in the order they come
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.parts.iter().filter_map(|part| match part {
+            Part::Placeholder(placeholder) => Some(placeholder.key.as_str()),
+            _ => None,
+        })
+    }
+
+    /// the lines of the template with the values of the data, a missing value
+    /// without fallback is empty, and still filled to its width
+    pub fn render(&self, data: &impl TemplateData) -> Vec<Line> {
+        let mut lines = vec![];
+        let mut spans = vec![];
+        for part in &self.parts {
+            match part {
+                Part::Text(text) => spans.push(Span::plain(text.as_str())),
+                Part::Newline => lines.push(Line::new(std::mem::take(&mut spans))),
+                Part::Placeholder(placeholder) => {
+                    let value = data
+                        .value(&placeholder.key)
+                        .or_else(|| placeholder.fallback.clone())
+                        .unwrap_or_default();
+                    let text = placeholder.format(&value);
+                    if !text.is_empty() {
+                        spans.push(Span::new(text, placeholder.style));
+                    }
+                }
+            }
+        }
+        lines.push(Line::new(spans));
+        lines
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = TemplateError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Template> for String {
+    fn from(value: Template) -> Self {
+        value.source
+    }
+}
+
+impl Placeholder {
+    /// round a decimal number or cut any other value to the precision,
+    /// then fill it to the width
+    fn format(&self, value: &str) -> String {
+        let value = match self.precision {
+            Some(precision) => match decimal(value.trim()) {
+                Some(number) => format!("{number:.precision$}"),
+                None => truncate(value, precision, ""),
+            },
+            None => value.to_string(),
+        };
+        match self.width {
+            Some(width) => pad(&value, width, self.align),
+            None => value,
+        }
+    }
+}
+
+/// the number when the value is written with digits and at most one point,
+/// so `nan`, `inf` and `1e400` stay as they are written
+fn decimal(value: &str) -> Option<f64> {
+    let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let is_digits = |s: &str| s.chars().all(|char| char.is_ascii_digit());
+    if whole.len() + fraction.len() == 0 || !is_digits(whole) || !is_digits(fraction) {
+        return None;
+    }
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+}
+
+fn parse_placeholder(body: &str, pos: usize) -> Result<Placeholder, TemplateError> {
+    let (name, spec) = body.split_once(':').unwrap_or((body, ""));
+    let (key, fallback) = match name.split_once('|') {
+        Some((key, fallback)) => (key, Some(fallback.to_string())),
+        None => (name, None),
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(TemplateError::EmptyKey(pos));
+    }
+    let mut placeholder = Placeholder {
+        key: key.to_string(),
+        fallback,
+        align: Alignment::Left,
+        width: None,
+        precision: None,
+        style: Style::new(),
+    };
+    let invalid = || TemplateError::InvalidSpec(spec.to_string());
+    let mut rest = spec;
+    if let Some(align) = rest.chars().next().and_then(|char| match char {
+        '<' => Some(Alignment::Left),
+        '>' => Some(Alignment::Right),
+        '^' => Some(Alignment::Center),
+        _ => None,
+    }) {
+        placeholder.align = align;
+        rest = &rest[1..];
+    }
+    let (width, after) = split_number(rest);
+    placeholder.width = width;
+    rest = after;
+    if let Some(after) = rest.strip_prefix('.')
+        && after.starts_with(|char: char| char.is_ascii_digit())
+    {
+        let (precision, after) = split_number(after);
+        placeholder.precision = precision;
+        rest = after;
+    }
+    if rest.len() != spec.len() && !rest.is_empty() {
+        rest = rest.strip_prefix('.').ok_or_else(invalid)?;
+    }
+    if !rest.is_empty() {
+        for word in rest.split('.') {
+            placeholder.style = apply_style_word(placeholder.style, word)?;
+        }
+    }
+    Ok(placeholder)
+}
+
+fn split_number(s: &str) -> (Option<usize>, &str) {
+    let len = s
+        .find(|char: char| !char.is_ascii_digit())
+        .unwrap_or(s.len());
+    (s[..len].parse().ok(), &s[len..])
+}
+
+fn apply_style_word(style: Style, word: &str) -> Result<Style, TemplateError> {
+    let unknown = || TemplateError::UnknownStyle(word.to_string());
+    Ok(match word {
+        "bold" => style.bold(),
+        "dim" => style.dim(),
+        "italic" => style.italic(),
+        "underline" => style.underline(),
+        "blink" => style.blink(),
+        "reverse" => style.reverse(),
+        "hidden" => style.hidden(),
+        "strikethrough" => style.strikethrough(),
+        word => match word.strip_prefix("on_") {
+            Some(color) => style.on(parse_color(color).ok_or_else(unknown)?),
+            None => style.fg(parse_color(word).ok_or_else(unknown)?),
+        },
+    })
+}
+
+fn parse_color(word: &str) -> Option<Color> {
+    if let Some(hex) = word.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+        return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+    }
+    let (bright, name) = match word.strip_prefix("bright_") {
+        Some(name) => (true, name),
+        None => (false, word),
+    };
+    let index = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ]
+    .iter()
+    .position(|color| *color == name)?;
+    Color::from_palette_index(index as u8 + if bright { 8 } else { 0 })
+}
+
+#[test]
+fn tst_template() {
+    let data = HashMap::from([("cpu", "Intel Core i7"), ("load", "0.4567")]);
+    let template =
+        Template::parse("{cpu:>15.5.underline}\n{{{load:.2}}} {gpu|-:^3.on_#ff8700}").unwrap();
+    let lines = template.render(&data);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+        lines[0].spans,
+        vec![Style::new().underline().paint("          Intel")]
+    );
+    assert_eq!(lines[1].plain_text(), "{0.46}  - ");
+    assert_eq!(
+        lines[1].spans[3].style,
+        Style::new().on(Color::Rgb(255, 135, 0))
+    );
+    let numbers = HashMap::from([("a", "nan"), ("b", "inf"), ("c", "1e400"), ("d", "-3.")]);
+    let formatted = Template::parse("{a:.5} {b:.5} {c:.5} {d:.1}")
+        .unwrap()
+        .render(&numbers);
+    assert_eq!(formatted[0].plain_text(), "nan inf 1e400 -3.0");
+    let missing = Template::parse("{gpu:>4}|{disk}|")
+        .unwrap()
+        .render(&numbers);
+    assert_eq!(missing[0].plain_text(), "    ||");
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::json!({ "cpu": { "cores": 8 }, "gpus": ["AMD"] });
+        let lines = Template::parse("{cpu.cores} {gpus.0} {gpus.1|none}")
+            .unwrap()
+            .render(&json);
+        assert_eq!(lines[0].plain_text(), "8 AMD none");
+    }
+    assert_eq!(
+        Template::parse("{cpu:bold.purple}"),
+        Err(TemplateError::UnknownStyle("purple".to_string()))
+    );
+    assert_eq!(Template::parse("a {cpu"), Err(TemplateError::Unclosed(2)));
+    assert_eq!(Template::parse("a }"), Err(TemplateError::Unopened(2)));
+    assert_eq!(
+        Template::parse("{cpu:10x}"),
+        Err(TemplateError::InvalidSpec("10x".to_string()))
+    );
+}
@@ -0,0 +1,148 @@
+//! what we know about the terminal we print to
+use std::io::IsTerminal;
+
+use terminal_size::{Height, Width, terminal_size};
+
+use crate::layout::ImageProtocol;
+
+/// the columns of the terminal, `COLUMNS` is used when stdout is not a terminal
+pub fn terminal_width() -> Option<usize> {
+    if let Some((Width(width), _)) = terminal_size() {
+        return Some(width as usize);
+    }
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|width| *width > 0)
+}
+
+/// the lines of the terminal, `LINES` is used when stdout is not a terminal
+pub fn terminal_height() -> Option<usize> {
+    if let Some((_, Height(height))) = terminal_size() {
+        return Some(height as usize);
+    }
+    std::env::var("LINES")
+        .ok()
+        .and_then(|lines| lines.trim().parse().ok())
+        .filter(|height| *height > 0)
+}
+
+/// the colors the terminal can show, colors it can not show are downgraded when drawing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorSupport {
+    /// no escape sequence at all, for a file or a pipe, the elements which show
+    /// only colors are left out
+    Plain,
+    /// no color, bold, italic and the other attributes are kept
+    None,
+    /// the sixteen colors of the palette
+    Ansi16,
+    /// the 256 colors
+    Ansi256,
+    #[default]
+    TrueColor,
+}
+
+impl ColorSupport {
+    /// detect the colors of stdout from `NO_COLOR`, `CLICOLOR_FORCE`, `CLICOLOR`,
+    /// `COLORTERM` and `TERM`, and whether stdout is a terminal, it is plain when
+    /// stdout is no terminal and the colors are not forced
+    pub fn detect() -> Self {
+        Self::from_env(
+            |name| std::env::var(name).ok(),
+            std::io::stdout().is_terminal(),
+        )
+    }
+
+    /// the same as [ColorSupport::detect], with the environment read from the closure
+    /// ```
+    /// use cliprint::terminal::ColorSupport;
+    /// let env = |name: &str| (name == "TERM").then(|| "xterm-256color".to_string());
+    /// assert_eq!(ColorSupport::from_env(env, true), ColorSupport::Ansi256);
+    /// assert_eq!(ColorSupport::from_env(env, false), ColorSupport::Plain);
+    /// ```
+    pub fn from_env(env: impl Fn(&str) -> Option<String>, is_tty: bool) -> Self {
+        let is_set = |name: &str| env(name).is_some_and(|value| !value.is_empty());
+        let forced = env("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0");
+        if !forced && !is_tty {
+            return ColorSupport::Plain;
+        }
+        if is_set("NO_COLOR") || (!forced && env("CLICOLOR").as_deref() == Some("0")) {
+            return ColorSupport::None;
+        }
+        let term = env("TERM").unwrap_or_default();
+        let level = match env("COLORTERM").as_deref() {
+            Some("truecolor") | Some("24bit") => ColorSupport::TrueColor,
+            _ if term.ends_with("-direct") || term.contains("truecolor") => ColorSupport::TrueColor,
+            _ if term.contains("256color") => ColorSupport::Ansi256,
+            _ if term == "dumb" => ColorSupport::None,
+            _ => ColorSupport::Ansi16,
+        };
+        if forced {
+            level.max(ColorSupport::Ansi16)
+        } else {
+            level
+        }
+    }
+}
+
+impl ImageProtocol {
+    /// detect the graphics protocol of the terminal from `KITTY_WINDOW_ID`, `TERM`
+    /// and `TERM_PROGRAM`, none when the terminal is not known to show pictures
+    pub fn detect() -> Option<Self> {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// the same as [ImageProtocol::detect], with the environment read from the closure
+    /// ```
+    /// use cliprint::layout::ImageProtocol;
+    /// let env = |name: &str| (name == "TERM").then(|| "xterm-kitty".to_string());
+    /// assert_eq!(ImageProtocol::from_env(env), Some(ImageProtocol::Kitty));
+    /// ```
+    pub fn from_env(env: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let term = env("TERM").unwrap_or_default();
+        let program = env("TERM_PROGRAM").unwrap_or_default();
+        if env("KITTY_WINDOW_ID").is_some() || term.contains("kitty") || program == "ghostty" {
+            return Some(ImageProtocol::Kitty);
+        }
+        match program.as_str() {
+            "iTerm.app" | "WezTerm" => return Some(ImageProtocol::Iterm2),
+            _ => {}
+        }
+        if term.starts_with("foot") || term.starts_with("mlterm") || term.contains("sixel") {
+            return Some(ImageProtocol::Sixel);
+        }
+        None
+    }
+}
+
+#[test]
+fn tst_color_support() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    assert_eq!(
+        ColorSupport::from_env(env(&[("COLORTERM", "truecolor")]), true),
+        ColorSupport::TrueColor
+    );
+    assert_eq!(
+        ColorSupport::from_env(env(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]), true),
+        ColorSupport::None
+    );
+    assert_eq!(
+        ColorSupport::from_env(env(&[("CLICOLOR_FORCE", "1"), ("TERM", "dumb")]), false),
+        ColorSupport::Ansi16
+    );
+    assert_eq!(
+        ColorSupport::from_env(env(&[("TERM", "xterm")]), true),
+        ColorSupport::Ansi16
+    );
+    assert_eq!(
+        ColorSupport::from_env(env(&[("NO_COLOR", "1")]), false),
+        ColorSupport::Plain
+    );
+}
@@ -0,0 +1,302 @@
+//! measure strings the way the terminal shows them
+//!
+//! escape sequences take no column, and every grapheme cluster takes
+//! the width the terminal gives it, so CJK and emoji take two columns
+//! ```
+//! use cliprint::text::display_width;
+//! assert_eq!(display_width("\x1b[1;36mname\x1b[0m"), 4);
+//! assert_eq!(display_width("你好"), 4);
+//! ```
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::layout::Alignment;
+
+const ESC: char = '\x1b';
+
+/// a part of a line, either an escape sequence or text shown on the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    Escape(&'a str),
+    Text(&'a str),
+}
+
+/// iterator over the [Token] of a str
+#[derive(Debug, Clone)]
+pub struct Tokens<'a> {
+    rest: &'a str,
+}
+
+/// split the str into escape sequences and visible text
+/// ```
+/// use cliprint::text::{tokens, Token};
+/// let parts: Vec<Token> = tokens("\x1b[31mred").collect();
+/// assert_eq!(parts, vec![Token::Escape("\x1b[31m"), Token::Text("red")]);
+/// ```
+pub fn tokens(s: &str) -> Tokens<'_> {
+    Tokens { rest: s }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let len = if self.rest.starts_with(ESC) {
+            escape_len(self.rest)
+        } else {
+            self.rest.find(ESC).unwrap_or(self.rest.len())
+        };
+        let (token, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        if token.starts_with(ESC) {
+            Some(Token::Escape(token))
+        } else {
+            Some(Token::Text(token))
+        }
+    }
+}
+
+/// the length in bytes of the escape sequence at the start of the str
+///
+/// CSI ends with a byte in `@..=~`, OSC ends with BEL or ST, and DCS, APC,
+/// PM and SOS end with ST. A sequence without end takes the rest of the str
+fn escape_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    match bytes.get(1) {
+        None => 1,
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map(|pos| pos + 3)
+            .unwrap_or(bytes.len()),
+        Some(b']') | Some(b'P') | Some(b'_') | Some(b'^') | Some(b'X') => {
+            let mut index = 2;
+            while index < bytes.len() {
+                match bytes[index] {
+                    0x07 if bytes[1] == b']' => return index + 1,
+                    0x1b if bytes.get(index + 1) == Some(&b'\\') => return index + 2,
+                    _ => index += 1,
+                }
+            }
+            bytes.len()
+        }
+        Some(_) => 1 + s[1..].chars().next().map(char::len_utf8).unwrap_or(0),
+    }
+}
+
+/// the columns a grapheme cluster takes, a terminal cell never holds more than two
+pub fn grapheme_width(grapheme: &str) -> usize {
+    grapheme.width().min(2)
+}
+
+/// the columns the str takes on the terminal
+pub fn display_width(s: &str) -> usize {
+    tokens(s)
+        .map(|token| match token {
+            Token::Escape(_) => 0,
+            Token::Text(text) => text.graphemes(true).map(grapheme_width).sum(),
+        })
+        .sum()
+}
+
+/// an escape sequence, or a grapheme cluster with its width
+#[derive(Debug, Clone, Copy)]
+enum Atom<'a> {
+    Escape(&'a str),
+    Grapheme(&'a str, usize),
+}
+
+fn atoms(s: &str) -> Vec<Atom<'_>> {
+    let mut output = vec![];
+    for token in tokens(s) {
+        match token {
+            Token::Escape(escape) => output.push(Atom::Escape(escape)),
+            Token::Text(text) => output.extend(
+                text.graphemes(true)
+                    .map(|grapheme| Atom::Grapheme(grapheme, grapheme_width(grapheme))),
+            ),
+        }
+    }
+    output
+}
+
+fn is_sgr(escape: &str) -> bool {
+    escape.starts_with("\x1b[") && escape.ends_with('m')
+}
+
+fn is_reset(escape: &str) -> bool {
+    escape == "\x1b[0m" || escape == "\x1b[m"
+}
+
+/// keep the sgr escapes which are still active after the atoms
+fn fold_style(style: &mut String, atoms: &[Atom<'_>]) {
+    for atom in atoms {
+        if let Atom::Escape(escape) = atom {
+            if is_reset(escape) {
+                style.clear();
+            } else if is_sgr(escape) {
+                style.push_str(escape);
+            }
+        }
+    }
+}
+
+/// cut the str so it takes at most width columns, the ellipsis ends the cut str
+///
+/// escape sequences are kept, so the styles are still closed after the cut
+/// ```
+/// use cliprint::text::truncate;
+/// assert_eq!(truncate("\x1b[36mabcdef\x1b[0m", 4, "…"), "\x1b[36mabc…\x1b[0m");
+/// assert_eq!(truncate("abc", 4, "…"), "abc");
+/// ```
+pub fn truncate(s: &str, width: usize, ellipsis: &str) -> String {
+    if display_width(s) <= width {
+        return s.to_string();
+    }
+    let ellipsis = if display_width(ellipsis) <= width {
+        ellipsis
+    } else {
+        ""
+    };
+    let budget = width - display_width(ellipsis);
+    let mut used = 0;
+    let mut cut = false;
+    let mut output = String::with_capacity(s.len());
+    for atom in atoms(s) {
+        match atom {
+            Atom::Escape(escape) => output.push_str(escape),
+            Atom::Grapheme(_, _) if cut => {}
+            Atom::Grapheme(grapheme, grapheme_width) => {
+                if used + grapheme_width > budget {
+                    cut = true;
+                    output.push_str(ellipsis);
+                } else {
+                    used += grapheme_width;
+                    output.push_str(grapheme);
+                }
+            }
+        }
+    }
+    output
+}
+
+/// break the str into lines which take at most width columns
+///
+/// lines break at spaces, a word longer than width is broken anywhere, and a
+/// grapheme wider than width is left out. A style which is active at the end of a line is closed there and
+/// opened again on the next line
+/// ```
+/// use cliprint::text::wrap;
+/// assert_eq!(wrap("Intel Core i7", 10), vec!["Intel Core", "i7"]);
+/// assert_eq!(wrap("abcdef", 4), vec!["abcd", "ef"]);
+/// ```
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    // a grapheme which can never fit would make the line wider than width
+    let atoms: Vec<Atom> = atoms(s)
+        .into_iter()
+        .map(|atom| match atom {
+            Atom::Grapheme(_, grapheme_width) if grapheme_width > width => Atom::Grapheme("", 0),
+            atom => atom,
+        })
+        .collect();
+    let mut lines = vec![];
+    let mut style = String::new();
+    let mut start = 0;
+    let mut used = 0;
+    let mut last_space = None;
+    let mut index = 0;
+    let mut emit = |from: usize, to: usize, next: usize, style: &mut String| {
+        let mut line = style.clone();
+        for atom in &atoms[from..to] {
+            match atom {
+                Atom::Escape(escape) => line.push_str(escape),
+                Atom::Grapheme(grapheme, _) => line.push_str(grapheme),
+            }
+        }
+        fold_style(style, &atoms[from..next]);
+        if !style.is_empty() {
+            line.push_str("\x1b[0m");
+        }
+        lines.push(line);
+    };
+    while index < atoms.len() {
+        let Atom::Grapheme(grapheme, grapheme_width) = atoms[index] else {
+            index += 1;
+            continue;
+        };
+        let is_space = grapheme.chars().all(char::is_whitespace);
+        if used + grapheme_width <= width {
+            if is_space {
+                last_space = Some(index);
+            }
+            used += grapheme_width;
+            index += 1;
+            continue;
+        }
+        let (end, next) = match last_space {
+            _ if is_space => (index, index + 1),
+            Some(space) => (space, space + 1),
+            None if used == 0 => (index + 1, index + 1),
+            None => (index, index),
+        };
+        emit(start, end, next, &mut style);
+        start = next;
+        used = 0;
+        last_space = None;
+        index = next;
+    }
+    emit(start, atoms.len(), atoms.len(), &mut style);
+    lines
+}
+
+/// fill the str with spaces until it takes width columns
+pub(crate) fn pad(content: &str, width: usize, layout: Alignment) -> String {
+    let fill = width.saturating_sub(display_width(content));
+    let mut output = String::with_capacity(content.len() + fill);
+    match layout {
+        Alignment::Left => {
+            output.push_str(content);
+            output.extend(std::iter::repeat_n(' ', fill));
+        }
+        Alignment::Right => {
+            output.extend(std::iter::repeat_n(' ', fill));
+            output.push_str(content);
+        }
+        Alignment::Center => {
+            output.extend(std::iter::repeat_n(' ', fill / 2));
+            output.push_str(content);
+            output.extend(std::iter::repeat_n(' ', fill - fill / 2));
+        }
+    }
+    output
+}
+
+#[test]
+fn tst_display_width() {
+    assert_eq!(display_width("sss"), 3);
+    assert_eq!(display_width("\x1b[1;36msss\x1b[0m"), 3);
+    assert_eq!(
+        display_width("\x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\"),
+        4
+    );
+    assert_eq!(display_width("日本語"), 6);
+    assert_eq!(display_width("e\u{301}"), 1);
+    assert_eq!(display_width("👨‍👩‍👧"), 2);
+    assert_eq!(display_width("\u{200b}"), 0);
+    assert_eq!(truncate("日本語", 4, "…"), "日…");
+    assert_eq!(truncate("abc", 2, "..."), "ab");
+    assert_eq!(
+        wrap("\x1b[1;36mIntel Core\x1b[0m i7", 6),
+        vec!["\x1b[1;36mIntel\x1b[0m", "\x1b[1;36mCore\x1b[0m", "i7"]
+    );
+    assert_eq!(wrap("日本語", 3), vec!["日", "本", "語"]);
+    assert_eq!(wrap("日a本 bc", 1), vec!["a", "b", "c"]);
+    assert_eq!(
+        pad("\x1b[31m日本\x1b[0m", 6, Alignment::Right),
+        "  \x1b[31m日本\x1b[0m"
+    );
+}
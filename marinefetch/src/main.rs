@@ -6,8 +6,16 @@ use waylandinfos::get_output_infos;
 use zbus::{Result, blocking::Connection, proxy};
 
 use cliprint::elements;
+use cliprint::gauge::gauge;
+use cliprint::image::Image;
 use cliprint::layout;
-use cliprint::layout::RowSettings;
+use cliprint::layout::{
+    GaugeSettings, HtmlSettings, ImageProtocol, Overflow, RowSettings, SvgSettings, TableSettings,
+    Theme,
+};
+use cliprint::live::watch;
+use cliprint::producer::{Producer, resolve};
+use cliprint::terminal::ColorSupport;
 use elements::CliElement;
 use layout::Alignment;
 
@@ -15,6 +23,9 @@ use nu_ansi_term::Color::Cyan;
 
 const ARCHLINUX: &str = include_str!("../assert/archlinux.txt");
 
+/// the columns the picture of the logo takes
+const LOGO_WIDTH: usize = 40;
+
 const UP_TIME: &str = "/proc/uptime";
 
 const MEMINFO: &str = "/proc/meminfo";
@@ -33,15 +44,19 @@ const PRODUCT_VERSION: &str = "/sys/devices/virtual/dmi/id/product_version";
 
 const CPU_INFO: &str = "/proc/cpuinfo";
 
+/// the longest time to wait for D-Bus, Wayland or a command
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(2);
+
 use std::sync::OnceLock;
+use std::time::Duration;
 
 fn get_connection() -> zbus::Result<Connection> {
     if let Some(cnx) = SESSION.get() {
         Ok(cnx.clone())
     } else {
+        // another thread may have connected in the meantime, its connection is kept
         let cnx = Connection::system()?;
-        SESSION.set(cnx.clone()).expect("Can't reset a OnceCell");
-        Ok(cnx)
+        Ok(SESSION.get_or_init(|| cnx).clone())
     }
 }
 
@@ -64,11 +79,15 @@ trait Hostname1 {
     fn operating_system_pretty_name(&self) -> Result<String>;
 }
 
+/// the hostname1 service, none when D-Bus can not be reached
+fn hostname1() -> Option<Hostname1ProxyBlocking<'static>> {
+    let connection = get_connection().ok()?;
+    Hostname1ProxyBlocking::new(&connection).ok()
+}
+
 fn get_hostname() -> String {
-    let connection = get_connection().unwrap();
-    let proxy = Hostname1ProxyBlocking::new(&connection).unwrap();
-    proxy
-        .static_hostname()
+    hostname1()
+        .and_then(|proxy| proxy.static_hostname().ok())
         .unwrap_or("UnownHostName".to_string())
 }
 
@@ -90,8 +109,24 @@ fn hostname_element() -> CliElement {
 }
 
 #[inline]
+/// the png or jpeg at `MARINEFETCH_LOGO`, drawn with half blocks when the terminal
+/// has no graphics protocol, the ascii logo when there is no such picture
 fn os_icon() -> CliElement {
-    CliElement::print_single_from_str_with_color(ARCHLINUX, Alignment::Left, Cyan, true)
+    std::env::var("MARINEFETCH_LOGO")
+        .ok()
+        .and_then(|path| std::fs::read(path).ok())
+        .and_then(|bytes| {
+            Image::from_png(&bytes)
+                .ok()
+                .or_else(|| Image::from_jpeg(&bytes).ok())
+        })
+        .map(|logo| {
+            let protocol = ImageProtocol::detect().unwrap_or(ImageProtocol::HalfBlock);
+            CliElement::print_image(logo, protocol, LOGO_WIDTH)
+        })
+        .unwrap_or_else(|| {
+            CliElement::print_single_from_str_with_color(ARCHLINUX, Alignment::Left, Cyan, true)
+        })
 }
 
 #[inline]
@@ -99,25 +134,11 @@ fn wm_name() -> String {
     std::env::var("XDG_CURRENT_DESKTOP").unwrap_or("nofind".to_string())
 }
 
-#[inline]
-fn wm_name_element() -> CliElement {
-    let wm_promote = Cyan.bold().paint("WM");
-    let wm_element = format!("{}: {}", wm_promote, wm_name());
-    CliElement::print_single(&[&wm_element], Alignment::Left)
-}
-
 #[inline]
 fn xdg_session_type() -> String {
     std::env::var("XDG_SESSION_TYPE").unwrap_or("Unknown".to_string())
 }
 
-#[inline]
-fn xdg_session_type_element() -> CliElement {
-    let xdg_promote = Cyan.bold().paint("SessionType");
-    let xdg_element = format!("{}: {}", xdg_promote, xdg_session_type());
-    CliElement::print_single(&[&xdg_element], Alignment::Left)
-}
-
 fn get_uptime() -> String {
     std::fs::read_to_string(UP_TIME)
         .map(|content| {
@@ -131,73 +152,67 @@ fn get_uptime() -> String {
         .unwrap_or("0".to_string())
 }
 
-fn uptime_element() -> CliElement {
-    let uptime_promote = Cyan.bold().paint("Uptime");
-    let uptime_element = format!("{}: {}", uptime_promote, get_uptime());
-    CliElement::print_single(&[&uptime_element], Alignment::Left)
-}
-
 fn get_kernel() -> String {
-    let connection = get_connection().unwrap();
-    let proxy = Hostname1ProxyBlocking::new(&connection).unwrap();
-    proxy.kernel_release().unwrap()
+    hostname1()
+        .and_then(|proxy| proxy.kernel_release().ok())
+        .unwrap_or("Unknown".to_string())
 }
 
-fn kernel_element() -> CliElement {
-    let kernel_promote = Cyan.bold().paint("Kernel");
-    let kernel_element = format!("{}: {}", kernel_promote, get_kernel());
-    CliElement::print_single(&[&kernel_element], Alignment::Left)
+fn get_os_name() -> String {
+    hostname1()
+        .and_then(|proxy| proxy.operating_system_pretty_name().ok())
+        .map(|name| format!("{} {}", name, ARCHTECHER))
+        .unwrap_or("Unknown".to_string())
 }
 
-fn get_os_name() -> String {
-    let connection = get_connection().unwrap();
-    let proxy = Hostname1ProxyBlocking::new(&connection).unwrap();
-    format!(
-        "{} {}",
-        proxy.operating_system_pretty_name().unwrap(),
-        ARCHTECHER
-    )
+/// a field of meminfo, in kiB
+fn meminfo_field(content: &str, name: &str) -> Option<u64> {
+    content
+        .lines()
+        .find(|line| line.starts_with(name))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|value| value.parse().ok())
 }
 
-fn os_name_element() -> CliElement {
-    let os_name_promote = Cyan.bold().paint("OS");
-    let os_name_element = format!("{}: {}", os_name_promote, get_os_name());
-    CliElement::print_single(&[&os_name_element], Alignment::Left)
+fn usage_gauge(used: u64, total: u64) -> String {
+    let settings = GaugeSettings {
+        width: 10,
+        ..Default::default()
+    };
+    gauge(used as f64 / total as f64, &settings).render()
 }
 
+/// the used memory is what is not available, the page cache and the buffers
+/// can be taken back, so they are not counted
 fn get_memory() -> String {
     std::fs::read_to_string(MEMINFO)
-        .map(|content| {
-            let lines: Vec<&str> = content.lines().collect();
-            let mut total_memory = String::new();
-            let mut free_memory = String::new();
-            for lin in lines {
-                if lin.starts_with("MemTotal:") {
-                    total_memory = lin
-                        .split(' ')
-                        .filter(|lin| !lin.is_empty())
-                        .collect::<Vec<&str>>()[1]
-                        .to_string();
-                }
-                if lin.starts_with("MemFree:") {
-                    free_memory = lin
-                        .split(' ')
-                        .filter(|line| !line.is_empty())
-                        .collect::<Vec<&str>>()[1]
-                        .to_string();
-                }
-            }
-            let total_memory: i32 = total_memory.parse::<i32>().unwrap() / 1024;
-            let free_memory: i32 = free_memory.parse::<i32>().unwrap() / 1024;
-            format!("{} MiB / {} MiB", free_memory, total_memory)
+        .ok()
+        .and_then(|content| {
+            let total_memory = meminfo_field(&content, "MemTotal:")?;
+            let available_memory = meminfo_field(&content, "MemAvailable:")?;
+            let used_memory = total_memory.saturating_sub(available_memory);
+            Some(format!(
+                "{} MiB / {} MiB {}",
+                used_memory / 1024,
+                total_memory / 1024,
+                usage_gauge(used_memory, total_memory)
+            ))
         })
         .unwrap_or("Unknown".to_string())
 }
 
-fn memory_element() -> CliElement {
-    let memory_promote = Cyan.bold().paint("Memory");
-    let memory_element = format!("{}: {}", memory_promote, get_memory());
-    CliElement::print_single(&[&memory_element], Alignment::Left)
+/// none when there is no swap
+fn get_swap() -> Option<String> {
+    let content = std::fs::read_to_string(MEMINFO).ok()?;
+    let total_swap = meminfo_field(&content, "SwapTotal:").filter(|total| *total > 0)?;
+    let free_swap = meminfo_field(&content, "SwapFree:")?;
+    let used_swap = total_swap.saturating_sub(free_swap);
+    Some(format!(
+        "{} MiB / {} MiB {}",
+        used_swap / 1024,
+        total_swap / 1024,
+        usage_gauge(used_swap, total_swap)
+    ))
 }
 
 fn get_shell() -> String {
@@ -206,12 +221,6 @@ fn get_shell() -> String {
         .unwrap_or("Unknown".to_string())
 }
 
-fn shell_element() -> CliElement {
-    let shell_promote = Cyan.bold().paint("Shell");
-    let shell_element = format!("{}: {}", shell_promote, get_shell());
-    CliElement::print_single(&[&shell_element], Alignment::Left)
-}
-
 fn get_terminal() -> String {
     std::env::var("TERM_PROGRAM").unwrap_or_else(|_| {
         std::process::Command::new("tty")
@@ -223,12 +232,6 @@ fn get_terminal() -> String {
     })
 }
 
-fn terminal_element() -> CliElement {
-    let terminal_promote = Cyan.bold().paint("Terminal");
-    let terminal_element = format!("{}: {}", terminal_promote, get_terminal());
-    CliElement::print_single(&[&terminal_element], Alignment::Left)
-}
-
 fn get_machine_name() -> String {
     let Ok(name) = std::fs::read_to_string(PRODUCT_NAME) else {
         return "Unknown".to_string();
@@ -241,12 +244,6 @@ fn get_machine_name() -> String {
     format!("{} {}", name, version)
 }
 
-fn machine_element() -> CliElement {
-    let machine_promote = Cyan.bold().paint("Host");
-    let machine_element = format!("{}: {}", machine_promote, get_machine_name());
-    CliElement::print_single(&[&machine_element], Alignment::Left)
-}
-
 fn get_cpu_name() -> String {
     let Ok(cpu) = std::fs::read_to_string(CPU_INFO) else {
         return "Unknown".to_string();
@@ -266,12 +263,6 @@ fn get_cpu_name() -> String {
     format!("{} ({})", cpunameinfos[0].trim(), number)
 }
 
-fn cpu_element() -> CliElement {
-    let cpu_promote = Cyan.bold().paint("CPU");
-    let cpu_element = format!("{}: {}", cpu_promote, get_cpu_name());
-    CliElement::print_single(&[&cpu_element], Alignment::Left)
-}
-
 fn get_gpu_names() -> Vec<String> {
     std::process::Command::new("lspci")
         .arg("-mm")
@@ -287,14 +278,15 @@ fn get_gpu_names() -> Vec<String> {
                     .split('"')
                     .filter(|line| !line.trim().is_empty())
                     .collect();
-                let infoname = information[1];
+                let [_, infoname, corporations, device, ..] = information[..] else {
+                    continue;
+                };
                 if infoname.starts_with("DISPLAY")
                     || infoname.starts_with("3D")
                     || infoname.starts_with("VGA")
                 {
-                    let corporations = information[2].split(' ').collect::<Vec<&str>>();
-                    let corporation = corporations[0];
-                    outputs.push(format!("{} {}", corporation, information[3]));
+                    let corporation = corporations.split(' ').next().unwrap_or_default();
+                    outputs.push(format!("{} {}", corporation, device));
                 }
             }
             outputs
@@ -302,12 +294,6 @@ fn get_gpu_names() -> Vec<String> {
         .unwrap_or_default()
 }
 
-fn gpu_element(gpu: &str) -> CliElement {
-    let gpu_promote = Cyan.bold().paint("GPU");
-    let gpu_element = format!("{}: {}", gpu_promote, gpu);
-    CliElement::print_single(&[&gpu_element], Alignment::Left)
-}
-
 fn color_block(start: usize, end: usize, step: usize) -> String {
     let mut color = String::new();
     use nu_ansi_term::Color;
@@ -326,17 +312,97 @@ fn color_emement() -> CliElement {
     CliElement::print_single(&[&color_block(0, 255, 9)], Alignment::Left)
 }
 
-fn wayland_screen(info: OutputInfo) -> CliElement {
-    let screen_promote = Cyan.bold().paint("Screen");
-    let screen_element = format!(
-        "{}: {} {}",
-        screen_promote,
+fn wayland_screen(info: OutputInfo) -> String {
+    format!(
+        "{} {}",
         info.name.unwrap_or("".to_string()),
         info.logical_size
             .map(|(x, y)| format!("{}x{}", x, y))
             .unwrap_or("Default".to_string())
-    );
-    CliElement::print_single(&[&screen_element], Alignment::Left)
+    )
+}
+
+fn info_settings() -> TableSettings {
+    TableSettings {
+        key_style: Cyan.bold().into(),
+        ..Default::default()
+    }
+}
+
+/// a row which is made at once
+fn info_row(key: &'static str, value: fn() -> String) -> Producer<'static, Vec<(String, String)>> {
+    Producer::new(async move { vec![(key.to_string(), value())] })
+}
+
+/// a row which waits on D-Bus or a command, it is made on a thread of its own
+fn slow_info_row(
+    key: &'static str,
+    value: fn() -> String,
+) -> Producer<'static, Vec<(String, String)>> {
+    Producer::spawn(move || vec![(key.to_string(), value())])
+        .timeout(SOURCE_TIMEOUT)
+        .fallback(vec![(key.to_string(), "Unknown".to_string())])
+}
+
+/// the rows of the infos, the slow sources are waited for at the same time
+fn info_rows() -> Vec<(String, String)> {
+    let screens = Producer::spawn(|| {
+        if xdg_session_type() != "wayland" {
+            return vec![];
+        }
+        get_output_infos()
+            .into_iter()
+            .map(|info| ("Screen".to_string(), wayland_screen(info)))
+            .collect()
+    });
+    let gpus = Producer::spawn(|| {
+        get_gpu_names()
+            .into_iter()
+            .map(|gpu| ("GPU".to_string(), gpu))
+            .collect()
+    });
+    let swap = Producer::new(async {
+        get_swap()
+            .map(|swap| ("Swap".to_string(), swap))
+            .into_iter()
+            .collect()
+    });
+    resolve(vec![
+        slow_info_row("OS", get_os_name),
+        info_row("Host", get_machine_name),
+        slow_info_row("Kernel", get_kernel),
+        info_row("Uptime", get_uptime),
+        info_row("Shell", get_shell),
+        info_row("WM", wm_name),
+        slow_info_row("Terminal", get_terminal),
+        info_row("SessionType", xdg_session_type),
+        screens.timeout(SOURCE_TIMEOUT),
+        info_row("CPU", get_cpu_name),
+        gpus.timeout(SOURCE_TIMEOUT),
+        info_row("Memory", get_memory),
+        swap,
+    ])
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+#[cfg(feature = "nightly")]
+fn infos_element() -> CliElement {
+    CliElement::print_table(
+        #[coroutine]
+        || {
+            for row in info_rows() {
+                yield row;
+            }
+            Some(info_settings())
+        },
+    )
+}
+
+#[cfg(not(feature = "nightly"))]
+fn infos_element() -> CliElement {
+    CliElement::print_table(info_rows().into_iter(), Some(info_settings()))
 }
 
 #[cfg(feature = "nightly")]
@@ -346,59 +412,23 @@ fn os_description() -> CliElement {
         || {
             yield hostname_element();
             yield CliElement::print_single(&["----------"], Alignment::Left);
-            yield os_name_element();
-            yield machine_element();
-            yield kernel_element();
-            yield uptime_element();
-            yield shell_element();
-            yield wm_name_element();
-            yield terminal_element();
-            yield xdg_session_type_element();
-            if xdg_session_type() == "wayland" {
-                for info in get_output_infos() {
-                    yield wayland_screen(info);
-                }
-            }
-            yield cpu_element();
-            let gpus = get_gpu_names();
-            for gpu in gpus {
-                yield gpu_element(&gpu);
-            }
-            yield memory_element();
+            yield infos_element();
         },
     )
 }
 
 #[cfg(not(feature = "nightly"))]
 fn os_description() -> CliElement {
-    let mut columns = vec![
+    let columns = vec![
         hostname_element(),
         CliElement::print_single(&["----------"], Alignment::Left),
-        os_name_element(),
-        machine_element(),
-        kernel_element(),
-        uptime_element(),
-        shell_element(),
-        wm_name_element(),
-        terminal_element(),
-        xdg_session_type_element(),
+        infos_element(),
     ];
-    if xdg_session_type() == "wayland" {
-        for info in get_output_infos() {
-            columns.push(wayland_screen(info));
-        }
-    }
-    columns.push(cpu_element());
-    let gpus = get_gpu_names();
-    for gpu in gpus {
-        columns.push(gpu_element(&gpu));
-    }
-    columns.push(memory_element());
     CliElement::print_column(columns.into_iter())
 }
 
 #[cfg(feature = "nightly")]
-fn main() {
+fn fetch_element() -> CliElement {
     CliElement::print_column(
         #[coroutine]
         || {
@@ -407,20 +437,63 @@ fn main() {
                 || {
                     yield os_icon();
                     yield os_description();
-                    Some(RowSettings { spacing: 1 })
+                    Some(RowSettings {
+                        spacing: 1,
+                        ..Default::default()
+                    })
                 },
             );
             yield color_emement();
         },
     )
-    .draw();
 }
 
 #[cfg(not(feature = "nightly"))]
-fn main() {
+fn fetch_element() -> CliElement {
     let rowelements = vec![os_icon(), os_description()];
-    let top = CliElement::print_row(rowelements.into_iter(), Some(RowSettings { spacing: 1 }));
-    CliElement::print_column([top, color_emement()].into_iter()).draw();
+    let top = CliElement::print_row(
+        rowelements.into_iter(),
+        Some(RowSettings {
+            spacing: 1,
+            ..Default::default()
+        }),
+    );
+    CliElement::print_column([top, color_emement()].into_iter())
+}
+
+/// with `--watch`, the output is drawn again every second until Ctrl-C,
+/// with `--html` or `--svg`, the output is printed as a html page or a svg screenshot,
+/// with `--plain`, or when stdout is no terminal, it is printed without escape sequences
+fn main() {
+    if std::env::args().any(|arg| arg == "--watch") {
+        watch(Duration::from_secs(1), fetch_element).expect("failed to write to stdout");
+    } else if std::env::args().any(|arg| arg == "--html") {
+        let settings = HtmlSettings {
+            standalone: true,
+            title: Some("marinefetch".to_string()),
+            theme: Some(Theme::dark()),
+        };
+        fetch_element()
+            .write_html(&mut std::io::stdout().lock(), &settings)
+            .expect("failed to write to stdout");
+    } else if std::env::args().any(|arg| arg == "--svg") {
+        let settings = SvgSettings {
+            title: Some("marinefetch".to_string()),
+            ..Default::default()
+        };
+        fetch_element()
+            .write_svg(&mut std::io::stdout().lock(), &settings)
+            .expect("failed to write to stdout");
+    } else {
+        let support = if std::env::args().any(|arg| arg == "--plain") {
+            ColorSupport::Plain
+        } else {
+            ColorSupport::detect()
+        };
+        CliElement::print_fit_terminal(fetch_element(), Overflow::Ellipsis)
+            .write_to_with(&mut std::io::stdout().lock(), support)
+            .expect("failed to write to stdout");
+    }
 }
 
 #[test]
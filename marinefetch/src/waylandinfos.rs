@@ -8,15 +8,20 @@ use sctk::{
     registry_handlers,
 };
 
+/// the outputs of the wayland server, none when it can not be reached
 pub fn get_output_infos() -> Vec<OutputInfo> {
     // We initialize the logger for the purpose of debugging.
     // Set `RUST_LOG=debug` to see extra debug information.
 
     // Try to connect to the Wayland server.
-    let conn = Connection::connect_to_env().unwrap();
+    let Ok(conn) = Connection::connect_to_env() else {
+        return vec![];
+    };
 
     // Now create an event queue and a handle to the queue so we can create objects.
-    let (globals, mut event_queue) = registry_queue_init(&conn).unwrap();
+    let Ok((globals, mut event_queue)) = registry_queue_init(&conn) else {
+        return vec![];
+    };
     let qh = event_queue.handle();
 
     // Initialize the registry handling so other parts of Smithay's client toolkit may bind
@@ -39,12 +44,14 @@ pub fn get_output_infos() -> Vec<OutputInfo> {
     //
     // After the globals are bound, we need to dispatch again so that events may be sent to the newly
     // created objects.
-    event_queue.roundtrip(&mut list_outputs).unwrap();
+    if event_queue.roundtrip(&mut list_outputs).is_err() {
+        return vec![];
+    }
 
     list_outputs
         .output_state
         .outputs()
-        .map(|output| list_outputs.output_state.info(&output).unwrap())
+        .filter_map(|output| list_outputs.output_state.info(&output))
         .collect()
 }
 